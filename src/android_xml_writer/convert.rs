@@ -11,6 +11,8 @@ pub struct Export {
 }
 
 pub fn write_xmls(config: Config) -> Result<Export, ConvertError> {
+    ConvertError::ensure_exists(&config.base_xcstrings)?;
+
     let result = swift_localizable_json_parser::parse_from_file(&config.base_xcstrings)?
         .localizable
         .localized_per_language()
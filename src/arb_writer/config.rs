@@ -0,0 +1,14 @@
+use clap::Parser;
+use std::path::PathBuf;
+
+#[derive(Clone, Debug, Parser)]
+pub struct Config {
+    /// The path to an existing .xcstrings file to export the localizations from
+    #[clap(long)]
+    pub path_to_xcstrings: PathBuf,
+    #[clap(long)]
+    pub write_in: PathBuf,
+    /// The files are named {prefix}_{locale}.arb, Flutter uses app by default
+    #[clap(long, default_value = "app")]
+    pub file_prefix: String,
+}
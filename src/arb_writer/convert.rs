@@ -0,0 +1,174 @@
+use crate::arb_writer::config::Config;
+use crate::docx_writer::convert::Export;
+use crate::error::ConvertError;
+use crate::json::OrderedObject;
+use crate::placeholders::{
+    placeholder_name, plural_position, specifiers, to_named, PLURAL_PLACEHOLDER,
+};
+use serde_json::{json, Map, Value};
+use std::collections::HashMap;
+use swift_localizable_json_parser::types::output::{PluralVariate, Translation};
+
+pub const LOCALE_KEY: &str = "@@locale";
+pub const METADATA_PREFIX: &str = "@";
+/// Apple uses zero for exactly zero items in every language, which is =0 in ICU
+pub const ICU_ZERO: &str = "=0";
+
+pub fn write_arbs(config: Config) -> Result<Vec<Export>, ConvertError> {
+    ConvertError::ensure_exists(&config.path_to_xcstrings)?;
+
+    let parsed = swift_localizable_json_parser::parse_from_file(&config.path_to_xcstrings)?;
+    let source_language = &parsed.translation.source_language;
+    let plural_positions = parsed
+        .localizable
+        .single_translation
+        .iter()
+        .map(|s| (s.key_raw.as_str(), plural_position(s, source_language)))
+        .collect::<HashMap<_, _>>();
+    let localized_per_language = parsed.localizable.localized_per_language();
+
+    std::fs::create_dir_all(&config.write_in).map_err(ConvertError::io(&config.write_in))?;
+
+    let mut exports = vec![];
+
+    for (language, translations) in &localized_per_language.language_localized {
+        log::debug!("Writing arb for language: {language}");
+
+        // Flutter uses underscores in the locale, e.g.: pt_BR
+        let locale = language.replace('-', "_");
+        let mut arb = OrderedObject::default();
+        let mut amount_keys = 0;
+
+        arb.insert(LOCALE_KEY, locale.clone());
+
+        for translation in &translations.translations {
+            let name = &translation.key_alphanumeric;
+            let plural_position = plural_positions
+                .get(translation.key_raw.as_str())
+                .copied()
+                .flatten();
+            let (value, base_specifiers) = match &translation.translation {
+                Translation::Localization(l) => {
+                    (to_named(&l.value, None, "{", "}"), specifiers(&l.value))
+                }
+                Translation::PluralVariation(pv) => {
+                    let cases = pv
+                        .iter()
+                        .filter(|pv| !pv.translation_value.value.is_empty())
+                        .map(|pv| {
+                            format!(
+                                "{}{{{}}}",
+                                icu_category(&pv.variate),
+                                to_named(&pv.translation_value.value, plural_position, "{", "}")
+                            )
+                        })
+                        .collect::<Vec<_>>();
+                    let value = if cases.is_empty() {
+                        String::new()
+                    } else {
+                        format!("{{{PLURAL_PLACEHOLDER}, plural, {}}}", cases.join(" "))
+                    };
+
+                    (
+                        value,
+                        pv.iter()
+                            .flat_map(|pv| specifiers(&pv.translation_value.value))
+                            .collect(),
+                    )
+                }
+            };
+
+            // Flutter falls back to the template for missing keys
+            if value.is_empty() {
+                continue;
+            }
+
+            if name.starts_with(|c: char| c.is_ascii_digit()) {
+                log::warn!("{name} is not a valid Dart identifier");
+            }
+
+            let mut metadata = Map::new();
+
+            if !translation.comment.is_empty() {
+                metadata.insert("description".to_string(), json!(translation.comment));
+            }
+
+            let mut placeholders = Map::new();
+
+            if matches!(translation.translation, Translation::PluralVariation(_)) {
+                placeholders.insert(PLURAL_PLACEHOLDER.to_string(), json!({"type": "int"}));
+            }
+
+            for specifier in base_specifiers {
+                placeholders.insert(
+                    placeholder_name(specifier.position, plural_position),
+                    json!({"type": specifier.placeholder_type()}),
+                );
+            }
+
+            if !placeholders.is_empty() {
+                metadata.insert("placeholders".to_string(), Value::Object(placeholders));
+            }
+
+            arb.insert(name, value);
+
+            if !metadata.is_empty() {
+                arb.insert(format!("{METADATA_PREFIX}{name}"), Value::Object(metadata));
+            }
+
+            amount_keys += 1;
+        }
+
+        let file_name = format!("{}_{}.arb", config.file_prefix, locale);
+
+        let path = config.write_in.join(&file_name);
+
+        std::fs::write(&path, arb.to_pretty_string()?).map_err(ConvertError::io(&path))?;
+
+        exports.push(Export {
+            amount_keys_to_translate: amount_keys,
+            amount_keys_total: amount_keys,
+            language_code: language.to_string(),
+            file_name,
+        });
+    }
+
+    Ok(exports)
+}
+
+pub fn icu_category(variate: &PluralVariate) -> String {
+    match variate {
+        PluralVariate::Zero => ICU_ZERO.to_string(),
+        _ => variate.android_key().to_string(),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::arb_writer::config::Config;
+    use std::env::current_dir;
+
+    #[test]
+    fn test_write_arbs() {
+        let base = current_dir().unwrap();
+        let write_in = base.join("generated").join("arb");
+
+        let _ = std::fs::remove_dir_all(&write_in);
+
+        let exports = super::write_arbs(Config {
+            path_to_xcstrings: base.join("resources").join("reader_test_base.xcstrings"),
+            write_in: write_in.clone(),
+            file_prefix: "app".to_string(),
+        })
+        .unwrap();
+
+        assert_eq!(2, exports.len());
+
+        let en = std::fs::read_to_string(write_in.join("app_en.arb")).unwrap();
+
+        assert!(en.contains(
+            "\"plurals\": \"{count, plural, one{{count} single} other{{count} multiple}}\""
+        ));
+        assert!(en.contains("\"description\": \"some random comment\""));
+    }
+}
@@ -0,0 +1,203 @@
+use crate::error::ConvertError;
+use std::fs::File;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// The hidden directory next to the catalog with the backups, so Xcode doesn't pick them up
+const BACKUP_DIR: &str = ".xcstringsdocx-backups";
+
+/// Keeps the previous version of a catalog before it is overwritten
+#[derive(Clone, Debug, Default, clap::Args)]
+pub struct Backups {
+    /// Keeps the previous catalog as a timestamped backup, only this amount of the newest backups is kept.
+    /// No backups are made if this is 0
+    #[clap(long, default_value_t = 0)]
+    pub keep_backups: usize,
+}
+
+/// Writes to a temporary file which is renamed into place, a rename within the same directory is atomic.
+/// The file is synced before and the directory after the rename, so a crash, power loss or full disk can't leave a truncated file behind.
+pub fn write_atomically(
+    path: &Path,
+    contents: impl AsRef<[u8]>,
+    backups: &Backups,
+) -> Result<(), ConvertError> {
+    if backups.keep_backups > 0 && path.exists() {
+        backup(path, backups.keep_backups)?;
+    }
+
+    let file_name = file_name(path);
+    let temp = path.with_file_name(format!(".{file_name}.tmp"));
+
+    write_synced(&temp, contents.as_ref()).map_err(|err| {
+        let _ = std::fs::remove_file(&temp);
+
+        ConvertError::io(&temp)(err)
+    })?;
+    std::fs::rename(&temp, path).map_err(|err| {
+        let _ = std::fs::remove_file(&temp);
+
+        ConvertError::io(path)(err)
+    })?;
+
+    sync_parent_dir(path)
+}
+
+fn write_synced(path: &Path, contents: &[u8]) -> std::io::Result<()> {
+    let mut file = File::create(path)?;
+
+    file.write_all(contents)?;
+    file.sync_all()
+}
+
+/// The rename is only durable once the directory entry is synced, directories can't be opened as a file on Windows
+#[cfg(unix)]
+fn sync_parent_dir(path: &Path) -> Result<(), ConvertError> {
+    let dir = match path.parent() {
+        Some(dir) if !dir.as_os_str().is_empty() => dir,
+        _ => Path::new("."),
+    };
+
+    File::open(dir)
+        .and_then(|dir| dir.sync_all())
+        .map_err(ConvertError::io(dir))
+}
+
+#[cfg(not(unix))]
+fn sync_parent_dir(_path: &Path) -> Result<(), ConvertError> {
+    Ok(())
+}
+
+/// The backups of the file, the newest first
+pub fn list_backups(path: &Path) -> Result<Vec<PathBuf>, ConvertError> {
+    let dir = backup_dir(path);
+
+    if !dir.exists() {
+        return Ok(vec![]);
+    }
+
+    let prefix = format!("{}.", file_name(path));
+    let mut backups = vec![];
+
+    for entry in std::fs::read_dir(&dir).map_err(ConvertError::io(&dir))? {
+        let entry = entry.map_err(ConvertError::io(&dir))?.path();
+
+        if entry
+            .file_name()
+            .is_some_and(|f| f.to_string_lossy().starts_with(&prefix))
+        {
+            backups.push(entry);
+        }
+    }
+
+    // The timestamps sort chronologically
+    backups.sort();
+    backups.reverse();
+
+    Ok(backups)
+}
+
+fn backup(path: &Path, keep_backups: usize) -> Result<(), ConvertError> {
+    let dir = backup_dir(path);
+
+    std::fs::create_dir_all(&dir).map_err(ConvertError::io(&dir))?;
+
+    let backup = dir.join(format!("{}.{}", file_name(path), timestamp()));
+
+    log::debug!("Backing up {:#?} to {:#?}", path, backup);
+
+    std::fs::copy(path, &backup).map_err(ConvertError::io(&backup))?;
+
+    for outdated in list_backups(path)?.iter().skip(keep_backups) {
+        log::debug!("Removing outdated backup: {:#?}", outdated);
+
+        std::fs::remove_file(outdated).map_err(ConvertError::io(outdated))?;
+    }
+
+    Ok(())
+}
+
+fn backup_dir(path: &Path) -> PathBuf {
+    path.parent()
+        .map(|p| p.to_path_buf())
+        .unwrap_or_default()
+        .join(BACKUP_DIR)
+}
+
+fn file_name(path: &Path) -> String {
+    path.file_name()
+        .map(|f| f.to_string_lossy().to_string())
+        .unwrap_or_default()
+}
+
+/// The current UTC time, formatted as 20240131T235959.123Z
+fn timestamp() -> String {
+    let since_epoch = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default();
+    let seconds = since_epoch.as_secs();
+    let (year, month, day) = civil_from_days((seconds / 86400) as i64);
+    let seconds_of_day = seconds % 86400;
+
+    format!(
+        "{year:04}{month:02}{day:02}T{:02}{:02}{:02}.{:03}Z",
+        seconds_of_day / 3600,
+        seconds_of_day % 3600 / 60,
+        seconds_of_day % 60,
+        since_epoch.subsec_millis()
+    )
+}
+
+/// The date of the days since 1970-01-01, see http://howardhinnant.github.io/date_algorithms.html#civil_from_days
+fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let days = days + 719468;
+    let era = days.div_euclid(146097);
+    let day_of_era = days.rem_euclid(146097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let mp = (5 * day_of_year + 2) / 153;
+    let day = (day_of_year - (153 * mp + 2) / 5 + 1) as u32;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+
+    (year, month, day)
+}
+
+#[cfg(test)]
+mod test {
+    use crate::backup::{civil_from_days, list_backups, write_atomically, Backups};
+    use std::env::current_dir;
+
+    #[test]
+    fn test_backups() {
+        assert_eq!((1970, 1, 1), civil_from_days(0));
+        assert_eq!((2024, 2, 29), civil_from_days(19782));
+
+        let dir = current_dir().unwrap().join("generated").join("backups");
+        let file = dir.join("Localizable.xcstrings");
+        let backups = Backups { keep_backups: 2 };
+
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+
+        for version in 0..4 {
+            write_atomically(&file, version.to_string(), &backups).unwrap();
+            // The timestamps have millisecond precision
+            std::thread::sleep(std::time::Duration::from_millis(2));
+        }
+
+        let listed = list_backups(&file).unwrap();
+
+        assert_eq!("3", std::fs::read_to_string(&file).unwrap());
+        assert_eq!(
+            vec!["2", "1"],
+            listed
+                .iter()
+                .map(|b| std::fs::read_to_string(b).unwrap())
+                .collect::<Vec<_>>()
+        );
+        assert!(!dir.join(".Localizable.xcstrings.tmp").exists());
+    }
+}
@@ -0,0 +1,43 @@
+use crate::docx_writer::config::Column;
+use clap::Parser;
+
+use std::path::PathBuf;
+
+#[derive(Clone, Debug, Parser)]
+pub struct Config {
+    #[clap(long)]
+    pub save_in: PathBuf,
+    #[clap(long)]
+    pub clean_dir_before_generating: bool,
+    /// If you added a new language, add it to the list so a new csv file can be generated
+    #[clap(long)]
+    pub new_language_codes: Vec<String>,
+    #[clap(long)]
+    pub path_to_xcstrings: PathBuf,
+    #[clap(long)]
+    pub columns_in_output: Vec<Column>,
+    #[clap(long, value_enum, default_value_t = Delimiter::Comma)]
+    pub delimiter: Delimiter,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, clap::ValueEnum)]
+pub enum Delimiter {
+    Comma,
+    Tab,
+}
+
+impl Delimiter {
+    pub fn byte(&self) -> u8 {
+        match self {
+            Delimiter::Comma => b',',
+            Delimiter::Tab => b'\t',
+        }
+    }
+
+    pub fn extension(&self) -> &'static str {
+        match self {
+            Delimiter::Comma => "csv",
+            Delimiter::Tab => "tsv",
+        }
+    }
+}
@@ -0,0 +1,112 @@
+use crate::csv_writer::config::Config;
+use crate::docx_writer::convert::Export;
+use crate::error::ConvertError;
+use crate::rows::{header, languages_to_write, rows_for_language};
+use swift_localizable_json_parser::types::output::Parsed;
+
+pub fn convert_from_path(config: Config) -> Result<Vec<Export>, ConvertError> {
+    ConvertError::ensure_exists(&config.path_to_xcstrings)?;
+
+    let read = std::fs::read(&config.path_to_xcstrings)
+        .map_err(ConvertError::io(&config.path_to_xcstrings))?;
+
+    convert_from_raw(&read, config)
+}
+
+pub fn convert_from_raw(xcstrings: &[u8], config: Config) -> Result<Vec<Export>, ConvertError> {
+    convert(
+        swift_localizable_json_parser::parse_from_bytes(xcstrings)?,
+        config,
+    )
+}
+
+pub fn convert(localizable: Parsed, config: Config) -> Result<Vec<Export>, ConvertError> {
+    let localized_per_language = localizable.localizable.localized_per_language();
+
+    if config.clean_dir_before_generating {
+        // Dir does not have to exist
+        let _ = std::fs::remove_dir_all(&config.save_in);
+    }
+
+    // Ignore any error
+    let _ = std::fs::create_dir_all(&config.save_in);
+
+    let base_language = &localizable.translation.source_language;
+    let mut exports = vec![];
+
+    for language_to_write in languages_to_write(
+        &localized_per_language,
+        base_language,
+        config.new_language_codes.clone(),
+    ) {
+        log::debug!(
+            "Writing {} for language: {language_to_write}",
+            config.delimiter.extension()
+        );
+
+        let file_name = format!("{}.{}", language_to_write, config.delimiter.extension());
+        // Quotes every field which contains a delimiter, quote or newline, so multiline values survive
+        let mut writer = csv::WriterBuilder::new()
+            .delimiter(config.delimiter.byte())
+            .from_path(config.save_in.join(&file_name))?;
+        let rows = rows_for_language(&localized_per_language, base_language, &language_to_write);
+
+        writer.write_record(header(
+            base_language,
+            &language_to_write,
+            &config.columns_in_output,
+        ))?;
+
+        for row in &rows {
+            writer.write_record(row.cells(&config.columns_in_output))?;
+        }
+
+        writer.flush()?;
+
+        exports.push(Export {
+            amount_keys_to_translate: rows.len() as i32,
+            amount_keys_total: rows.len() as i32,
+            language_code: language_to_write,
+            file_name,
+        })
+    }
+
+    Ok(exports)
+}
+
+#[cfg(test)]
+mod test {
+    use crate::csv_writer::config::{Config, Delimiter};
+    use std::env::current_dir;
+
+    #[test]
+    fn test_convert() {
+        let base = current_dir().unwrap();
+        let save_in = base.join("generated").join("csv");
+
+        for delimiter in [Delimiter::Comma, Delimiter::Tab] {
+            super::convert_from_path(Config {
+                save_in: save_in.clone(),
+                clean_dir_before_generating: false,
+                new_language_codes: vec!["pl".to_string()],
+                path_to_xcstrings: base.join("resources").join("reader_test_base.xcstrings"),
+                columns_in_output: vec![],
+                delimiter,
+            })
+            .unwrap();
+
+            let path = save_in.join(format!("nl.{}", delimiter.extension()));
+            let extracted = crate::xcstrings_csv_merger::csv_extractor::extract(&path)
+                .unwrap()
+                .remove(0);
+            let newlines = extracted
+                .extracted
+                .iter()
+                .find(|e| e.key == "Inlined\nwith \n\n newlines")
+                .unwrap();
+
+            assert_eq!("nl", extracted.language_code);
+            assert_eq!("Ingelijnd\nmet \n\n newlines", newlines.translated);
+        }
+    }
+}
@@ -0,0 +1,588 @@
+use crate::error::ConvertError;
+use crate::rows::{rows_for_language, variate_from_key, Row};
+use serde_json::{json, Map, Value};
+use std::collections::BTreeMap;
+use swift_localizable_json_parser::types::inoutoutput::{StringUnitContainer, TranslationValue};
+use swift_localizable_json_parser::types::input::TranslationTypeContainer;
+use swift_localizable_json_parser::types::output::{
+    LocalizedPerLanguage, LocalizedPerLanguageInfo, Parsed, PluralVariate,
+    SingleLocalizedPerLanguage, SinglePluralVariation, Translation,
+};
+use swift_localizable_json_parser::TRANSLATED_STATE;
+
+const DEVICE_UNIT_ID_SEPARATOR: &str = "|==|";
+const DEVICE_VARIATION_PREFIX: &str = "device.";
+
+/// The devices a string can vary by, in the order Xcode shows them
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum DeviceVariate {
+    IPhone,
+    IPod,
+    IPad,
+    Mac,
+    AppleWatch,
+    AppleTv,
+    AppleVision,
+    Other,
+}
+
+impl DeviceVariate {
+    pub const ALL: [DeviceVariate; 8] = [
+        DeviceVariate::IPhone,
+        DeviceVariate::IPod,
+        DeviceVariate::IPad,
+        DeviceVariate::Mac,
+        DeviceVariate::AppleWatch,
+        DeviceVariate::AppleTv,
+        DeviceVariate::AppleVision,
+        DeviceVariate::Other,
+    ];
+
+    /// The key in the catalog, e.g.: iphone
+    pub fn key(&self) -> &'static str {
+        match self {
+            DeviceVariate::IPhone => "iphone",
+            DeviceVariate::IPod => "ipod",
+            DeviceVariate::IPad => "ipad",
+            DeviceVariate::Mac => "mac",
+            DeviceVariate::AppleWatch => "applewatch",
+            DeviceVariate::AppleTv => "appletv",
+            DeviceVariate::AppleVision => "applevision",
+            DeviceVariate::Other => "other",
+        }
+    }
+
+    pub fn from_key(key: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|device| device.key().eq_ignore_ascii_case(key))
+    }
+}
+
+/// The value of the variation column of a device row, e.g.: device.iphone, or device.iphone.one if it also varies by plural.
+/// The prefix keeps the other device apart from the other plural variate.
+pub fn device_variation_text(device: DeviceVariate, variation: Option<&PluralVariate>) -> String {
+    match variation {
+        None => format!("{DEVICE_VARIATION_PREFIX}{}", device.key()),
+        Some(variation) => format!(
+            "{DEVICE_VARIATION_PREFIX}{}.{}",
+            device.key(),
+            variation.android_key().to_lowercase()
+        ),
+    }
+}
+
+/// The reverse of [device_variation_text], None if the text isn't a device variation
+pub fn split_device_variation(text: &str) -> Option<(DeviceVariate, Option<PluralVariate>)> {
+    let text = text.strip_prefix(DEVICE_VARIATION_PREFIX)?;
+
+    match text.split_once('.') {
+        None => Some((DeviceVariate::from_key(text)?, None)),
+        Some((device, variate)) => Some((
+            DeviceVariate::from_key(device)?,
+            Some(variate_from_key(variate)?),
+        )),
+    }
+}
+
+/// The unit id of a device row, the counterpart of [crate::xliff_writer::convert::unit_id]
+pub fn device_unit_id(
+    key: &str,
+    device: DeviceVariate,
+    variation: Option<&PluralVariate>,
+) -> String {
+    format!(
+        "{key}{DEVICE_UNIT_ID_SEPARATOR}{}",
+        device_variation_text(device, variation)
+    )
+}
+
+/// The keys with device variations in any language. The parser can't read these, so they are taken out of the catalog
+/// and kept as raw json: merging only touches the merged branches, every other branch is written back as it was.
+#[derive(Clone, Debug, Default)]
+pub struct DeviceVariations {
+    source_language: String,
+    strings: BTreeMap<String, Value>,
+}
+
+impl DeviceVariations {
+    /// Takes the keys with device variations out of the raw catalog
+    pub fn take_from(raw: &mut Value) -> Self {
+        let source_language = raw
+            .get("sourceLanguage")
+            .and_then(Value::as_str)
+            .unwrap_or_default()
+            .to_string();
+        let mut strings = BTreeMap::new();
+
+        if let Some(all) = raw.get_mut("strings").and_then(Value::as_object_mut) {
+            let keys = all
+                .iter()
+                .filter(|(_, string)| has_device_variations(string))
+                .map(|(key, _)| key.clone())
+                .collect::<Vec<_>>();
+
+            for key in keys {
+                if let Some(string) = all.remove(&key) {
+                    strings.insert(key, string);
+                }
+            }
+        }
+
+        Self {
+            source_language,
+            strings,
+        }
+    }
+
+    /// The keys with device variations of the raw catalog, the catalog itself is left as is
+    pub fn from_raw(raw: &Value) -> Self {
+        Self::take_from(&mut raw.clone())
+    }
+
+    /// Puts the keys back in a catalog
+    pub fn join(&self, catalog: &mut Value) {
+        if let Some(all) = catalog.get_mut("strings").and_then(Value::as_object_mut) {
+            for (key, string) in &self.strings {
+                all.insert(key.clone(), string.clone());
+            }
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.strings.is_empty()
+    }
+
+    pub fn contains_key(&self, key: &str) -> bool {
+        self.strings.contains_key(key)
+    }
+
+    /// Every language that has a localization of a key with device variations
+    pub fn languages(&self) -> Vec<String> {
+        let mut languages = self
+            .strings
+            .values()
+            .filter_map(|string| string.get("localizations").and_then(Value::as_object))
+            .flat_map(|localizations| localizations.keys().cloned())
+            .collect::<Vec<_>>();
+
+        languages.sort();
+        languages.dedup();
+
+        languages
+    }
+
+    /// A row per device (and plural variate) of every key. The devices of both the base language and the language are written,
+    /// a device the base language doesn't have is translated from its other device.
+    /// The plural variates per device follow [rows_for_language].
+    pub fn rows_for_language(&self, language: &str) -> Vec<Row> {
+        let base_language = &self.source_language;
+        let mut per_device = BTreeMap::<Option<DeviceVariate>, LocalizedPerLanguage>::new();
+
+        for (key, string) in &self.strings {
+            let localization =
+                |language: &str| string.get("localizations").and_then(|l| l.get(language));
+            // Like the parser, the key is the base value if the base language isn't in the catalog
+            let inlined = json!({ "stringUnit": { "state": TRANSLATED_STATE, "value": key } });
+            let base_branches = branches(localization(base_language).unwrap_or(&inlined));
+            let branches_language = match localization(language) {
+                Some(localization) if language != base_language => branches(localization),
+                _ => vec![],
+            };
+            let mut devices = base_branches
+                .iter()
+                .chain(&branches_language)
+                .map(|(device, _)| *device)
+                .collect::<Vec<_>>();
+
+            // Without device variations the value is the value of the other device
+            if devices.iter().any(Option::is_some) {
+                devices = devices
+                    .into_iter()
+                    .map(|device| device.or(Some(DeviceVariate::Other)))
+                    .collect();
+            }
+
+            devices.sort();
+            devices.dedup();
+
+            for device in devices {
+                let base = match branch(&base_branches, device)
+                    .or_else(|| branch(&base_branches, Some(DeviceVariate::Other)))
+                    .or_else(|| base_branches.first().map(|(_, value)| *value))
+                    .and_then(to_translation)
+                {
+                    None => continue,
+                    Some(base) => base,
+                };
+                // A plural translation of a plain base value (or the other way around) can't be written as rows
+                let translation = branch(&branches_language, device)
+                    .and_then(to_translation)
+                    .filter(|t| is_plural(t) == is_plural(&base));
+                let comment = string
+                    .get("comment")
+                    .and_then(Value::as_str)
+                    .unwrap_or_default();
+                let localized = per_device
+                    .entry(device)
+                    .or_insert_with(|| LocalizedPerLanguage {
+                        source_language: base_language.clone(),
+                        language_localized: BTreeMap::from([(
+                            base_language.clone(),
+                            LocalizedPerLanguageInfo::default(),
+                        )]),
+                    });
+                let single = |translation: Translation| SingleLocalizedPerLanguage {
+                    key_raw: key.clone(),
+                    key_alphanumeric: key.clone(),
+                    translation,
+                    comment: comment.to_string(),
+                };
+
+                localized
+                    .language_localized
+                    .entry(base_language.clone())
+                    .or_default()
+                    .translations
+                    .push(single(base));
+
+                if let Some(translation) = translation {
+                    localized
+                        .language_localized
+                        .entry(language.to_string())
+                        .or_default()
+                        .translations
+                        .push(single(translation));
+                }
+            }
+        }
+
+        let mut rows = vec![];
+
+        for (device, localized) in &per_device {
+            for mut row in rows_for_language(localized, base_language, language) {
+                row.device = *device;
+                rows.push(row);
+            }
+        }
+
+        // Stable, so the rows of a key keep the device order
+        rows.sort_by(|a, b| a.key.cmp(&b.key));
+
+        rows
+    }
+
+    /// The current translation, the counterpart of [Self::apply]
+    pub(crate) fn string_unit(
+        &self,
+        language: &str,
+        key: &str,
+        device: Option<DeviceVariate>,
+        variation: Option<&PluralVariate>,
+    ) -> Option<StringUnitContainer> {
+        let localization = self.strings.get(key)?.get("localizations")?.get(language)?;
+        let container = match localization.pointer("/variations/device") {
+            Some(devices) => devices.get(device.unwrap_or(DeviceVariate::Other).key())?,
+            None if device.is_none() || device == Some(DeviceVariate::Other) => localization,
+            None => return None,
+        };
+        let string_unit = match variation {
+            None => container.get("stringUnit")?,
+            Some(variation) => container.pointer(&format!(
+                "/variations/plural/{}/stringUnit",
+                variation.android_key().to_lowercase()
+            ))?,
+        };
+
+        Some(StringUnitContainer {
+            string_unit: serde_json::from_value(string_unit.clone()).ok()?,
+        })
+    }
+
+    /// Sets a translation, a localization without device variations gets them when a device is set,
+    /// the existing value becomes the value of the other device
+    pub(crate) fn apply(
+        &mut self,
+        language: &str,
+        key: &str,
+        device: Option<DeviceVariate>,
+        variation: Option<&PluralVariate>,
+        value: TranslationValue,
+    ) -> Result<(), ConvertError> {
+        let string = match self.strings.get_mut(key) {
+            None => {
+                return Err(ConvertError::MissingKey {
+                    key: key.to_string(),
+                });
+            }
+            Some(string) => string,
+        };
+        let localization = &mut string["localizations"][language];
+        let has_devices = localization.pointer("/variations/device").is_some();
+        let container = if device.is_none() && !has_devices {
+            localization
+        } else {
+            if !has_devices {
+                let existing = std::mem::take(localization);
+                let mut devices = Map::new();
+
+                if existing.as_object().is_some_and(|e| !e.is_empty()) {
+                    devices.insert(DeviceVariate::Other.key().to_string(), existing);
+                }
+
+                *localization = json!({ "variations": { "device": devices } });
+            }
+
+            &mut localization["variations"]["device"][device.unwrap_or(DeviceVariate::Other).key()]
+        };
+        let string_unit = json!({ "state": value.state, "value": value.value });
+
+        match variation {
+            None => {
+                if container.get("variations").is_some() {
+                    return Err(ConvertError::MissingVariation {
+                        key: key.to_string(),
+                    });
+                }
+
+                container["stringUnit"] = string_unit;
+            }
+            Some(variation) => {
+                if container.get("stringUnit").is_some() {
+                    return Err(ConvertError::UnexpectedVariation {
+                        key: key.to_string(),
+                    });
+                }
+
+                container["variations"]["plural"][variation.android_key().to_lowercase()] =
+                    json!({ "stringUnit": string_unit });
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Parses a catalog, the keys with device variations are returned separately
+pub fn parse(xcstrings: &[u8]) -> Result<(Parsed, DeviceVariations), ConvertError> {
+    let mut raw: Value = serde_json::from_slice(xcstrings)?;
+    let devices = DeviceVariations::take_from(&mut raw);
+    let parsed = swift_localizable_json_parser::parse_from_string(raw.to_string())?;
+
+    Ok((parsed, devices))
+}
+
+/// [rows_for_language] with the rows of the keys with device variations, ordered by key
+pub fn rows_with_devices(
+    localized_per_language: &LocalizedPerLanguage,
+    base_language: &str,
+    devices: &DeviceVariations,
+    language: &str,
+) -> Vec<Row> {
+    let mut rows = rows_for_language(localized_per_language, base_language, language);
+
+    if !devices.is_empty() {
+        rows.extend(devices.rows_for_language(language));
+        rows.sort_by(|a, b| a.key.cmp(&b.key));
+    }
+
+    rows
+}
+
+fn has_device_variations(string: &Value) -> bool {
+    string
+        .get("localizations")
+        .and_then(Value::as_object)
+        .is_some_and(|localizations| {
+            localizations
+                .values()
+                .any(|l| l.pointer("/variations/device").is_some())
+        })
+}
+
+/// The values of a localization per device, a single branch without device for a localization without device variations
+fn branches(localization: &Value) -> Vec<(Option<DeviceVariate>, &Value)> {
+    match localization
+        .pointer("/variations/device")
+        .and_then(Value::as_object)
+    {
+        None => vec![(None, localization)],
+        Some(devices) => {
+            for device in devices.keys() {
+                if DeviceVariate::from_key(device).is_none() {
+                    log::warn!("Skipping unknown device: {device}");
+                }
+            }
+
+            DeviceVariate::ALL
+                .iter()
+                .filter_map(|device| {
+                    devices
+                        .get(device.key())
+                        .map(|value| (Some(*device), value))
+                })
+                .collect()
+        }
+    }
+}
+
+/// The value of the device, the value without device variations counts as the other device
+fn branch<'a>(
+    branches: &[(Option<DeviceVariate>, &'a Value)],
+    device: Option<DeviceVariate>,
+) -> Option<&'a Value> {
+    branches
+        .iter()
+        .find(|(d, _)| *d == device)
+        .or_else(|| {
+            (device == Some(DeviceVariate::Other))
+                .then(|| branches.iter().find(|(d, _)| d.is_none()))
+                .flatten()
+        })
+        .map(|(_, value)| *value)
+}
+
+/// Reads a device branch like the parser reads a localization
+fn to_translation(value: &Value) -> Option<Translation> {
+    match serde_json::from_value::<TranslationTypeContainer>(value.clone()).ok()? {
+        TranslationTypeContainer::StringUnit(su) => Some(Translation::Localization(su.string_unit)),
+        TranslationTypeContainer::Variation(v) => {
+            let plural = v.variations.plural;
+
+            Some(Translation::PluralVariation(
+                [
+                    (plural.zero, PluralVariate::Zero),
+                    (plural.one, PluralVariate::One),
+                    (plural.two, PluralVariate::Two),
+                    (plural.few, PluralVariate::Few),
+                    (plural.many, PluralVariate::Many),
+                    (plural.other, PluralVariate::Other),
+                ]
+                .into_iter()
+                .filter_map(|(container, variate)| {
+                    container.map(|c| SinglePluralVariation {
+                        variate,
+                        translation_value: c.string_unit,
+                    })
+                })
+                .collect(),
+            ))
+        }
+    }
+}
+
+fn is_plural(translation: &Translation) -> bool {
+    matches!(translation, Translation::PluralVariation(_))
+}
+
+#[cfg(test)]
+mod test {
+    use crate::devices::{parse, split_device_variation, DeviceVariate};
+    use swift_localizable_json_parser::types::inoutoutput::TranslationValue;
+    use swift_localizable_json_parser::types::output::PluralVariate;
+
+    const CATALOG: &str = include_str!("../resources/device_test_base.xcstrings");
+
+    #[test]
+    fn test_device_rows() {
+        let (parsed, devices) = parse(CATALOG.as_bytes()).unwrap();
+
+        assert!(parsed.translation.strings.contains_key("plain"));
+        assert!(!parsed.translation.strings.contains_key("tap"));
+        assert_eq!(vec!["en", "nl"], devices.languages());
+
+        let rows = devices.rows_for_language("pl");
+
+        assert_eq!(
+            vec![
+                "device.iphone",
+                "device.mac",
+                "device.other.one",
+                "device.other.few",
+                "device.other.many",
+                "device.other.other"
+            ],
+            rows.iter().map(|r| r.variation_text()).collect::<Vec<_>>()
+        );
+        assert_eq!("%lld presses", rows[4].base_value);
+        assert!(rows.iter().all(|r| r.comment == "Button"));
+
+        let rows = devices.rows_for_language("nl");
+
+        assert_eq!("Klik", rows[1].translated_value);
+        assert_eq!(
+            Some((DeviceVariate::Mac, None)),
+            split_device_variation(&rows[1].variation_text())
+        );
+        assert_eq!(
+            Some((DeviceVariate::Other, Some(PluralVariate::Other))),
+            split_device_variation("device.other.other")
+        );
+        assert_eq!(None, split_device_variation("other"));
+        assert_eq!(None, split_device_variation("N/A"));
+    }
+
+    #[test]
+    fn test_apply() {
+        let (_, mut devices) = parse(CATALOG.as_bytes()).unwrap();
+        let value = |value: &str| TranslationValue {
+            state: "translated".to_string(),
+            value: value.to_string(),
+        };
+
+        devices
+            .apply("nl", "tap", Some(DeviceVariate::IPhone), None, value("Tik"))
+            .unwrap();
+        devices
+            .apply(
+                "nl",
+                "tap",
+                Some(DeviceVariate::Other),
+                Some(&PluralVariate::One),
+                value("%lld keer"),
+            )
+            .unwrap();
+        devices
+            .apply(
+                "de",
+                "tap",
+                Some(DeviceVariate::IPhone),
+                None,
+                value("Tippen"),
+            )
+            .unwrap();
+
+        assert_eq!(
+            "unexpected_variation",
+            devices
+                .apply(
+                    "nl",
+                    "tap",
+                    Some(DeviceVariate::Mac),
+                    Some(&PluralVariate::One),
+                    value("Klik")
+                )
+                .unwrap_err()
+                .code()
+        );
+
+        let mut catalog = serde_json::json!({ "strings": {} });
+
+        devices.join(&mut catalog);
+
+        let nl = &catalog["strings"]["tap"]["localizations"]["nl"]["variations"]["device"];
+
+        // The existing mac branch is kept
+        assert_eq!("Klik", nl["mac"]["stringUnit"]["value"]);
+        assert_eq!("Tik", nl["iphone"]["stringUnit"]["value"]);
+        assert_eq!(
+            "%lld keer",
+            nl["other"]["variations"]["plural"]["one"]["stringUnit"]["value"]
+        );
+        assert_eq!(
+            "Tippen",
+            catalog["strings"]["tap"]["localizations"]["de"]["variations"]["device"]["iphone"]
+                ["stringUnit"]["value"]
+        );
+    }
+}
@@ -20,55 +20,70 @@ pub enum TranslatedStatus {
     NoMatchingXcstringsKey(Vec<String>),
 }
 
-pub fn read(config: Config) -> Result<Export, ConvertError> {
-    let extracted = extract(&config.extract_from_docx)?;
-    let translated_status = if let Some(xcstrings) = &config.base_xcstrings {
-        // Make sure all keys that are in the .docx files contains at least all keys of the uploaded .xcstrings file
-        // else the merging will fail later
-        let parsed = swift_localizable_json_parser::parse_from_file(xcstrings)?;
-        let all_keys = parsed
-            .translation
-            .strings
-            .iter()
-            .map(|s| s.0.to_string())
-            .collect::<HashSet<_>>();
-        // Also create a hashset here, since plurals will be shown double since it's a vec
-        // and that's not what we want, since some languages has more plural rules
-        let current_language_keys = extracted
-            .extracted
-            .iter()
-            .map(|e| e.key.to_string())
-            .collect::<HashSet<_>>();
-        let difference = current_language_keys
-            .difference(&all_keys)
-            .cloned()
-            .collect::<Vec<_>>();
+/// A single export per language of the document
+pub fn read(config: Config) -> Result<Vec<Export>, ConvertError> {
+    let containers = extract(&config.extract_from_docx)?;
+    let parsed = match &config.base_xcstrings {
+        None => None,
+        Some(xcstrings) => Some((
+            swift_localizable_json_parser::parse_from_file(xcstrings)?,
+            super::super::xcstrings_metadata::read::extract(xcstrings)?,
+        )),
+    };
+    let mut exports = vec![];
 
-        if difference.is_empty() {
-            super::super::xcstrings_metadata::read::extract(xcstrings)?
-                .export
+    for extracted in containers {
+        let translated_status = if let Some((parsed, metadata)) = &parsed {
+            // Make sure all keys that are in the .docx files contains at least all keys of the uploaded .xcstrings file
+            // else the merging will fail later
+            let all_keys = parsed
+                .translation
+                .strings
+                .iter()
+                .map(|s| s.0.to_string())
+                .collect::<HashSet<_>>();
+            // Also create a hashset here, since plurals will be shown double since it's a vec
+            // and that's not what we want, since some languages has more plural rules
+            let current_language_keys = extracted
+                .extracted
                 .iter()
-                .find(|e| e.language_code == extracted.language_code)
-                .map(|export| {
-                    TranslatedStatus::Translated(export.localized_keys + export.not_localized_keys)
-                })
-                .unwrap_or(TranslatedStatus::NotYetInXcstrings)
+                .map(|e| e.key.to_string())
+                .collect::<HashSet<_>>();
+            let difference = current_language_keys
+                .difference(&all_keys)
+                .cloned()
+                .collect::<Vec<_>>();
+
+            if difference.is_empty() {
+                metadata
+                    .export
+                    .iter()
+                    .find(|e| e.language_code == extracted.language_code)
+                    .map(|export| {
+                        TranslatedStatus::Translated(
+                            export.localized_keys + export.not_localized_keys,
+                        )
+                    })
+                    .unwrap_or(TranslatedStatus::NotYetInXcstrings)
+            } else {
+                TranslatedStatus::NoMatchingXcstringsKey(difference)
+            }
         } else {
-            TranslatedStatus::NoMatchingXcstringsKey(difference)
-        }
-    } else {
-        TranslatedStatus::NoXcstringsFile
-    };
+            TranslatedStatus::NoXcstringsFile
+        };
+
+        exports.push(Export {
+            localized_keys: extracted
+                .extracted
+                .iter()
+                .filter(|e| !e.translated.is_empty())
+                .count(),
+            language_code: extracted.language_code,
+            translated_status,
+        });
+    }
 
-    Ok(Export {
-        language_code: extracted.language_code,
-        localized_keys: extracted
-            .extracted
-            .iter()
-            .filter(|e| !e.translated.is_empty())
-            .count(),
-        translated_status,
-    })
+    Ok(exports)
 }
 
 #[cfg(test)]
@@ -84,7 +99,8 @@ mod test {
             extract_from_docx: base.join("reader_test_updated_nl.docx"),
             base_xcstrings: Some(base.join("reader_test_base.xcstrings")),
         })
-        .unwrap();
+        .unwrap()
+        .remove(0);
 
         match export.translated_status {
             TranslatedStatus::Translated(_) => {}
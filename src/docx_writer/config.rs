@@ -15,9 +15,53 @@ pub struct Config {
     pub path_to_xcstrings: PathBuf,
     #[clap(long)]
     pub columns_in_output: Vec<Column>,
+    /// TMX files to pre-fill empty translations with exact matches, these rows get the prefilled state
+    #[clap(long)]
+    pub translation_memories: Vec<PathBuf>,
+    /// Other .xcstrings files to take suggestions from, next to the translation memories
+    #[clap(long)]
+    pub suggestion_catalogs: Vec<PathBuf>,
+    /// The minimum similarity in percentages of a suggestion
+    #[clap(long, default_value_t = 70)]
+    pub suggestion_threshold: u8,
+    /// Writes the languages of a group in a single docx file with a column per language, comma separated, e.g. nl,de
+    #[clap(long)]
+    pub language_groups: Vec<String>,
+    #[clap(flatten)]
+    pub filter: Filter,
+}
+
+/// Limits the rows to write, all filters must match
+#[derive(Clone, Debug, Default, clap::Args)]
+pub struct Filter {
+    /// Only writes rows without a translation or with the new state
+    #[clap(long)]
+    pub only_untranslated: bool,
+    /// Only writes keys matching the glob, * matches any text and ? a single character
+    #[clap(long)]
+    pub key_glob: Option<String>,
+    /// Only writes keys matching the regex
+    #[clap(long)]
+    pub key_regex: Option<String>,
+    /// Only writes keys with one of these extraction states, e.g. manual or stale
+    #[clap(long)]
+    pub extraction_states: Vec<String>,
+    /// Skips keys marked with shouldTranslate: false
+    #[clap(long)]
+    pub skip_should_not_translate: bool,
+    /// Only writes rows whose base value was added or changed since this older .xcstrings file,
+    /// the old base value is written in a Previous source column
+    #[clap(long)]
+    pub changed_since: Option<PathBuf>,
+    /// Same as changed_since, but reads path_to_xcstrings at this git revision of the local repository, e.g. a release tag
+    #[clap(long)]
+    pub changed_since_revision: Option<String>,
 }
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, clap::ValueEnum)]
 pub enum Column {
+    /// Reviewers can change the state of a row, the merger honours it
     State,
+    /// The best fuzzy match of the translation memories for untranslated rows, only in docx files
+    Suggestion,
 }
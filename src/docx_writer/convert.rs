@@ -5,12 +5,17 @@ use serde::Serialize;
 #[derive(Clone, Debug, Serialize)]
 pub struct Export {
     pub amount_keys_to_translate: i32,
+    /// The amount of rows before filtering
+    pub amount_keys_total: i32,
     pub language_code: String,
     pub file_name: String,
 }
 
 pub fn convert_from_path(config: config::Config) -> Result<Vec<Export>, ConvertError> {
-    let read = std::fs::read(&config.path_to_xcstrings)?;
+    ConvertError::ensure_exists(&config.path_to_xcstrings)?;
+
+    let read = std::fs::read(&config.path_to_xcstrings)
+        .map_err(ConvertError::io(&config.path_to_xcstrings))?;
 
     convert_from_raw(&read, config)
 }
@@ -20,7 +25,8 @@ pub fn convert_from_raw(
     config: config::Config,
 ) -> Result<Vec<Export>, ConvertError> {
     let converted = convert(
-        swift_localizable_json_parser::parse_from_bytes(xcstrings)?,
+        crate::devices::parse(xcstrings)?.0,
+        &serde_json::from_slice(xcstrings)?,
         config,
     )?;
 
@@ -29,23 +35,31 @@ pub fn convert_from_raw(
 
 use docx_rust::document::{Paragraph, RunContent, Table, TableCell, TableRow, Text, TextSpace};
 use docx_rust::formatting::{
-    Bold, BoldComplex, BorderStyle, BottomBorder, CharacterProperty, InsideHorizonBorder,
-    InsideVerticalBorder, LeftBorder, OnOffOnlyType, ParagraphProperty, RightBorder, TableBorders,
+    Bold, BoldComplex, BorderStyle, BottomBorder, CharacterProperty, Highlight, HighlightType, InsideHorizonBorder,
+    InsideVerticalBorder, LeftBorder, NoProof, OnOffOnlyType, ParagraphProperty, RightBorder, TableBorders,
     TableProperty, TableRowProperty, TopBorder,
 };
 
+use docx_rust::core::Core;
 use docx_rust::Docx;
 
 use docx_rust::document::ParagraphContent::Run;
-use std::collections::HashSet;
-use swift_localizable_json_parser::NEW_STATE;
 
+use crate::devices::{rows_with_devices, DeviceVariations};
 use crate::docx_writer::config::{Column, Config};
-use crate::{KEY_KEY, KEY_VARIATION, KEY_COMMENT};
-use swift_localizable_json_parser::types::output::{Parsed, Translation};
-
+use crate::docx_writer::filter::RowFilter;
+use crate::fingerprint::{to_description, Fingerprints};
+use crate::placeholders::{is_format_string, split_specifiers};
+use crate::rows::{group_languages, languages_to_write, per_language_header, PLURAL_VARIATES};
+use crate::translation_memory::TranslationMemory;
+use crate::{KEY_KEY, KEY_VARIATION, KEY_COMMENT, KEY_STATE, KEY_SUGGESTION, KEY_PREVIOUS_SOURCE};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use swift_localizable_json_parser::types::output::Parsed;
+
+/// The raw catalog contains the properties the parser drops, which are needed to filter, and the device variations
 pub fn convert(
     localizable: Parsed,
+    raw: &serde_json::Value,
     config: Config,
 ) -> std::result::Result<Vec<Export>, ConvertError> {
     let localized_per_language = localizable.localizable.localized_per_language();
@@ -59,28 +73,34 @@ pub fn convert(
     let _ = std::fs::create_dir(&config.save_in);
 
     let base_language = &localizable.translation.source_language;
-    let base_translation = localized_per_language
-        .language_localized
-        .get(base_language)
-        .unwrap()
-        .translations
-        .clone();
-    let mut languages_to_write_docx_files = localized_per_language
-        .language_localized
-        .keys()
-        .map(|k| k.to_string())
-        .collect::<HashSet<_>>();
+    let devices = DeviceVariations::from_raw(raw);
+    let mut new_language_codes = config.new_language_codes;
 
-    languages_to_write_docx_files.extend(config.new_language_codes);
+    // A language can have translations of keys with device variations only
+    new_language_codes.extend(devices.languages());
 
-    assert!(languages_to_write_docx_files.remove(base_language));
+    let languages_to_write_docx_files =
+        languages_to_write(&localized_per_language, base_language, new_language_codes);
+
+    let row_filter = RowFilter::new(
+        &config.filter,
+        raw,
+        &config.path_to_xcstrings,
+        &languages_to_write_docx_files,
+    )?;
+    let translation_memory = TranslationMemory::read(&config.translation_memories)?;
+    // Other catalogs are only suggested, never pre-filled
+    let mut suggestion_memory = translation_memory.clone();
+
+    for suggestion_catalog in &config.suggestion_catalogs {
+        suggestion_memory.add_from_xcstrings(suggestion_catalog)?;
+    }
 
     let mut exports = vec![];
 
-    for language_to_write in languages_to_write_docx_files {
-        log::debug!("Writing language: {language_to_write}");
+    for languages in group_languages(&config.language_groups, languages_to_write_docx_files)? {
+        log::debug!("Writing languages: {languages:?}");
 
-        let mut amount_keys_to_translate = 0;
         let table_border_style = BorderStyle::Single;
         let table_border_size = 4isize;
         let mut table_borders = TableBorders::default()
@@ -146,6 +166,15 @@ pub fn convert(
             }};
         }
 
+        // A document with a single language keeps the plain headers
+        let column_header = |header: &str, language: &str| {
+            if languages.len() == 1 {
+                header.to_string()
+            } else {
+                per_language_header(header, language)
+            }
+        };
+
         let mut base_table_row = TableRow::default()
             .property(TableRowProperty::default().table_header(OnOffOnlyType::On))
             .push_cell(add_header!(KEY_KEY))
@@ -153,151 +182,223 @@ pub fn convert(
             .push_cell(add_header!(KEY_VARIATION));
 
         if config.columns_in_output.contains(&Column::State) {
-            base_table_row = base_table_row.push_cell(add_header!("State"));
+            for language in &languages {
+                base_table_row =
+                    base_table_row.push_cell(add_header!(column_header(KEY_STATE, language)));
+            }
         }
 
-        base_table_row = base_table_row
-            .push_cell(add_header!(base_language.to_string()))
-            .push_cell(add_header!(language_to_write.clone()));
+        if row_filter.has_previous_sources() {
+            base_table_row = base_table_row.push_cell(add_header!(KEY_PREVIOUS_SOURCE));
+        }
+
+        base_table_row = base_table_row.push_cell(add_header!(base_language.to_string()));
+
+        for language in &languages {
+            if config.columns_in_output.contains(&Column::Suggestion) {
+                base_table_row =
+                    base_table_row.push_cell(add_header!(column_header(KEY_SUGGESTION, language)));
+            }
+
+            base_table_row = base_table_row.push_cell(add_header!(language.clone()));
+        }
 
         let mut table = Table::default()
             .property(TableProperty::default().borders(table_borders))
             .push_row(base_table_row);
 
-        macro_rules! write_translation {
-            ($table_row: expr, $translation_value: expr, $base_translation_value: expr) => {{
-                let state = $translation_value
-                    .clone()
-                    .map(|t| t.state)
-                    .unwrap_or(NEW_STATE.to_string());
-                let value = $translation_value
-                    .map(|t| t.value)
-                    .unwrap_or("".to_string());
+        let file_name = format!("{}.docx", languages.join("_"));
+        let rows_per_language = languages
+            .iter()
+            .map(|language| {
+                rows_with_devices(&localized_per_language, base_language, &devices, language)
+            })
+            .collect::<Vec<_>>();
+        // Languages can have other plural variates and devices, so the rows are joined by unit id.
+        // A unit is written when a row of any of the languages matches the filter.
+        let mut units = vec![];
+        let mut included = HashSet::new();
+        let mut key_positions = HashMap::new();
+
+        for (rows, language) in rows_per_language.iter().zip(&languages) {
+            for row in rows {
+                let next_position = key_positions.len();
+                let key_position = *key_positions
+                    .entry(row.key.clone())
+                    .or_insert(next_position);
+                let unit_id = row.unit_id();
+
+                if row_filter.matches(row, language) && included.insert(unit_id.clone()) {
+                    let variate_position = row
+                        .variation
+                        .as_ref()
+                        .and_then(|v| PLURAL_VARIATES.iter().position(|p| p == v));
+
+                    units.push(((key_position, row.device, variate_position), unit_id));
+                }
+            }
+        }
 
-                let mut new_table_row = $table_row;
+        // The variations only a later language has, e.g. few of pl, are kept next to the other variations of the key
+        units.sort();
 
-                if config.columns_in_output.contains(&Column::State) {
-                    new_table_row = new_table_row.push_cell(Paragraph::default().push_text(state));
-                }
+        let unit_ids = units.into_iter().map(|(_, unit_id)| unit_id).collect::<Vec<_>>();
 
-                new_table_row = new_table_row
-                    .push_cell(create_table_cell($base_translation_value))
-                    .push_cell(create_table_cell(&value));
+        let mut fingerprints = BTreeMap::new();
+        let mut rows_by_unit_id = vec![];
 
-                table = table.push_row(new_table_row);
+        for (rows, language) in rows_per_language.into_iter().zip(&languages) {
+            let amount_keys_total = rows.len() as i32;
+            let mut rows = rows
+                .into_iter()
+                .filter(|row| included.contains(&row.unit_id()))
+                .collect::<Vec<_>>();
 
-                amount_keys_to_translate += 1;
-            }};
-        }
+            // Prefilled translations are not in the catalog yet, so the fingerprints are taken before
+            fingerprints.insert(language.clone(), Fingerprints::from_rows(&rows));
 
-        let localization_language_to = localized_per_language
-            .language_localized
-            .get(&language_to_write)
-            .cloned()
-            .unwrap_or_default();
-        for localized_base_translation in &base_translation {
-            let existing_translation = localization_language_to
-                .translations
-                .iter()
-                .find(|t| t.key_raw == localized_base_translation.key_raw)
-                .map(|t| t.translation.clone());
+            let prefilled = translation_memory.prefill(&mut rows, base_language, language);
+            let amount_keys_to_translate = rows.len() as i32;
 
-            let table_row = TableRow::default()
-                .push_cell(create_table_cell(&localized_base_translation.key_raw))
-                .push_cell(create_table_cell(&localized_base_translation.comment));
+            log::debug!("Prefilled {prefilled} translations from translation memories");
+            log::debug!(
+                "Exported {amount_keys_to_translate} of {amount_keys_total} translations for language: {language}"
+            );
 
-            match &localized_base_translation.translation {
-                Translation::Localization(base) => {
-                    let table_row = table_row
-                        .clone()
-                        .push_cell(Paragraph::default().push_text("N/A"));
-                    let existing = existing_translation.map(|e| e.expect_localization());
+            exports.push(Export {
+                amount_keys_to_translate,
+                amount_keys_total,
+                language_code: language.clone(),
+                file_name: file_name.clone(),
+            });
+            rows_by_unit_id.push(
+                rows.into_iter()
+                    .map(|row| (row.unit_id(), row))
+                    .collect::<HashMap<_, _>>(),
+            );
+        }
 
-                    write_translation!(table_row, existing, &base.value)
+        for unit_id in &unit_ids {
+            let (row, row_language) = rows_by_unit_id
+                .iter()
+                .zip(&languages)
+                .find_map(|(rows, language)| rows.get(unit_id).map(|row| (row, language)))
+                .unwrap();
+            let mut table_row = TableRow::default()
+                .push_cell(create_table_cell(&row.key))
+                .push_cell(create_table_cell(&row.comment))
+                .push_cell(Paragraph::default().push_text(row.variation_text()));
+
+            if config.columns_in_output.contains(&Column::State) {
+                for rows in &rows_by_unit_id {
+                    let state = rows
+                        .get(unit_id)
+                        .map(|row| row.state.clone())
+                        .unwrap_or_default();
+
+                    table_row = table_row.push_cell(Paragraph::default().push_text(state));
                 }
-                Translation::PluralVariation(base) => {
-                    // First handle the default plural variations from the base language
-                    for pv in base {
-                        let table_row = table_row
-                            .clone()
-                            .push_cell(Paragraph::default().push_text(pv.variate.android_key()));
-
-                        let existing = existing_translation.clone().and_then(|et| {
-                            et.expect_plural_variation()
-                                .iter()
-                                .find(|existing| existing.variate == pv.variate)
-                                .map(|pv| pv.translation_value.clone())
-                        });
-
-                        write_translation!(table_row, existing, &pv.translation_value.value)
-                    }
-
-                    // It could also be that the language already has other plural variations than the base language, check that
-                    if let Some(e) = existing_translation {
-                        for pv in e.expect_plural_variation() {
-                            if base.iter().any(|p| p.variate == pv.variate) {
-                                // Already a variation for it
-                                continue;
-                            }
-
-                            let mut table_row = table_row.clone();
-
-                            table_row = table_row.push_cell(
-                                Paragraph::default().push_text(pv.variate.android_key()),
-                            );
-
-                            write_translation!(table_row, Some(pv.translation_value.clone()), &"");
-                        }
-                    }
+            }
+
+            if row_filter.has_previous_sources() {
+                table_row = table_row
+                    .push_cell(create_table_cell(row_filter.previous_source(row, row_language)));
+            }
+
+            table_row = table_row.push_cell(create_table_cell(&row.base_value));
+
+            for (rows, language) in rows_by_unit_id.iter().zip(&languages) {
+                let translated_value = rows
+                    .get(unit_id)
+                    .map(|row| row.translated_value.as_str())
+                    .unwrap_or_default();
+
+                if config.columns_in_output.contains(&Column::Suggestion) {
+                    let suggestion = if translated_value.is_empty() {
+                        suggestion_memory
+                            .best_fuzzy_match(
+                                base_language,
+                                &row.base_value,
+                                language,
+                                config.suggestion_threshold,
+                            )
+                            .map(|(suggestion, percentage)| format!("({percentage}%) {suggestion}"))
+                            .unwrap_or_default()
+                    } else {
+                        String::new()
+                    };
+
+                    table_row = table_row.push_cell(create_table_cell(suggestion));
                 }
+
+                table_row = table_row.push_cell(create_table_cell(translated_value));
             }
+
+            table = table.push_row(table_row);
         }
 
         docx.document.push(table);
+        docx.core = Some(Core {
+            description: Some(to_description(&fingerprints)?.into()),
+            ..Default::default()
+        });
 
-        let file_name = format!("{}.docx", language_to_write);
         let write_to = config.save_in.join(&file_name);
         let _ = std::fs::remove_file(&write_to);
 
         docx.write_file(write_to)?;
-
-        log::debug!(
-            "Exported {amount_keys_to_translate} translations for language: {language_to_write}"
-        );
-
-        exports.push(Export {
-            amount_keys_to_translate,
-            language_code: language_to_write,
-            file_name,
-        })
     }
 
     Ok(exports)
 }
 
+/// Specifiers are highlighted, so translators notice them and leave them intact.
+/// Every part is a run of its own, the extractor concatenates them to the exact text.
 fn create_table_cell<T: ToString>(text: T) -> TableCell<'static> {
     let mut table_cell = TableCell::default();
     let text = text.to_string();
+    let is_format_string = is_format_string(&text);
     let split = text.split('\n');
 
     for split in split {
-        let text = Text {
-            space: if split.trim() == split {
-                None
-            } else {
-                Some(TextSpace::Preserve)
-            },
-            text: split.to_string().into(),
-        };
+        let mut paragraph = Paragraph::default();
+
+        for (part, is_specifier) in split_specifiers(split, is_format_string) {
+            let text = Text {
+                space: if part.trim() == part {
+                    None
+                } else {
+                    Some(TextSpace::Preserve)
+                },
+                text: part.to_string().into(),
+            };
+            let mut run = docx_rust::document::Run::default().push_text(text);
+
+            if is_specifier {
+                run = run.property(specifier_property());
+            }
+
+            paragraph = paragraph.push(run);
+        }
 
-        table_cell
-            .content
-            .push(Paragraph::default().push_text(text).into());
+        table_cell.content.push(paragraph.into());
     }
 
     table_cell
 }
 
+fn specifier_property() -> CharacterProperty<'static> {
+    let mut property = CharacterProperty::default().color("7030A0");
+
+    property.highlight = Some(Highlight {
+        value: Some(HighlightType::LightGray),
+    });
+    // Spell checking marks most specifiers as typos
+    property.no_proof = Some(NoProof { value: Some(true) });
+
+    property
+}
+
 #[cfg(debug_assertions)]
 pub fn write_generated_docxs() -> std::path::PathBuf {
     let base = std::env::current_dir().unwrap();
@@ -320,6 +421,11 @@ pub fn write_generated_docxs() -> std::path::PathBuf {
             new_language_codes: vec!["pl".to_string()],
             columns_in_output: vec![crate::docx_writer::config::Column::State],
             path_to_xcstrings: Default::default(),
+            translation_memories: vec![],
+            suggestion_catalogs: vec![],
+            suggestion_threshold: 70,
+            language_groups: vec![],
+            filter: Default::default(),
         },
     )
     .unwrap();
@@ -340,10 +446,105 @@ pub fn write_generated_docxs() -> std::path::PathBuf {
 
 #[cfg(test)]
 mod test {
-    use crate::docx_writer::convert::write_generated_docxs;
+    use crate::docx_writer::convert::{create_table_cell, write_generated_docxs};
+    use crate::xcstrings_docx_merger::convert::extract_text_from_table_row_content;
+    use docx_rust::document::TableRowContent;
 
     #[test]
     fn test_convert() {
         write_generated_docxs();
     }
+
+    #[test]
+    fn test_specifier_runs() {
+        for text in [
+            "Keys to localize: %1$lld (%2$lld%%)",
+            "%@ and %@\\n second line",
+            "Save 100% now",
+            "First %@\nsecond %@",
+        ] {
+            let cell = TableRowContent::TableCell(create_table_cell(text));
+
+            assert_eq!(text, extract_text_from_table_row_content(&cell).unwrap());
+        }
+    }
+
+    #[test]
+    fn test_language_groups() {
+        let save_in = std::env::current_dir()
+            .unwrap()
+            .join("generated")
+            .join("grouped");
+        let exports = super::convert_from_raw(
+            include_bytes!("../../resources/reader_test_base.xcstrings"),
+            crate::docx_writer::config::Config {
+                save_in: save_in.clone(),
+                clean_dir_before_generating: true,
+                new_language_codes: vec!["pl".to_string()],
+                columns_in_output: vec![crate::docx_writer::config::Column::State],
+                path_to_xcstrings: Default::default(),
+                translation_memories: vec![],
+                suggestion_catalogs: vec![],
+                suggestion_threshold: 70,
+                language_groups: vec!["nl,pl".to_string()],
+                filter: Default::default(),
+            },
+        )
+        .unwrap();
+
+        assert_eq!(2, exports.len());
+        assert!(exports.iter().all(|e| e.file_name == "nl_pl.docx"));
+
+        let extracted =
+            crate::xcstrings_docx_merger::docx_extractor::extract(&save_in.join("nl_pl.docx"))
+                .unwrap();
+
+        assert_eq!(
+            vec!["nl", "pl"],
+            extracted
+                .iter()
+                .map(|e| e.language_code.as_str())
+                .collect::<Vec<_>>()
+        );
+        assert!(extracted.iter().all(|e| e.fingerprints.is_some()));
+
+        // The variations only pl has are next to the other variations of the key
+        let keys = extracted[1]
+            .extracted
+            .iter()
+            .map(|e| e.key.as_str())
+            .collect::<Vec<_>>();
+        let plurals = keys.iter().filter(|k| **k == "plurals").count();
+        let first_plural = keys.iter().position(|k| *k == "plurals").unwrap();
+        let variations = extracted[1].extracted[first_plural..first_plural + plurals]
+            .iter()
+            .map(|e| e.variation.as_ref().map(|v| v.android_key()))
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            vec![Some("one"), Some("few"), Some("many"), Some("other")],
+            variations
+        );
+    }
+
+    #[test]
+    fn test_plural_categories() {
+        // The fixture is what test_convert writes for Polish, which has more plural categories than English
+        let extracted = crate::xcstrings_docx_merger::docx_extractor::extract(
+            &std::env::current_dir()
+                .unwrap()
+                .join("resources")
+                .join("reader_test_base_pl.docx"),
+        )
+        .unwrap()
+        .remove(0);
+        let variates = extracted
+            .extracted
+            .iter()
+            .filter(|e| e.key == "plurals")
+            .filter_map(|e| e.variation.as_ref().map(|v| v.android_key()))
+            .collect::<Vec<_>>();
+
+        assert_eq!(vec!["one", "few", "many", "other"], variates);
+    }
 }
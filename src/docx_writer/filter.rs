@@ -0,0 +1,287 @@
+use crate::devices::rows_with_devices;
+use crate::docx_writer::config::Filter;
+use crate::error::ConvertError;
+use crate::rows::Row;
+use regex::Regex;
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+use std::process::Command;
+use swift_localizable_json_parser::NEW_STATE;
+
+/// [Filter] with the patterns compiled and the keys it excludes by their raw catalog properties
+pub(crate) struct RowFilter {
+    only_untranslated: bool,
+    key_patterns: Vec<Regex>,
+    /// The parser drops extractionState and shouldTranslate, so these are read from the raw catalog
+    excluded_keys: HashSet<String>,
+    /// The base values per language and unit id of the older catalog, if only changed rows are written.
+    /// Languages have other plural variates, e.g. the few row of pl has the base value of other
+    previous_sources: Option<HashMap<String, HashMap<String, String>>>,
+}
+
+impl RowFilter {
+    pub(crate) fn new(
+        filter: &Filter,
+        raw: &serde_json::Value,
+        path_to_xcstrings: &Path,
+        languages: &HashSet<String>,
+    ) -> Result<Self, ConvertError> {
+        let mut key_patterns = vec![];
+
+        if let Some(glob) = &filter.key_glob {
+            key_patterns.push(glob_to_regex(glob)?);
+        }
+
+        if let Some(regex) = &filter.key_regex {
+            key_patterns.push(
+                Regex::new(regex)
+                    .map_err(|e| ConvertError::from(format!("Invalid key regex: {e}")))?,
+            );
+        }
+
+        let mut excluded_keys = HashSet::new();
+
+        if let Some(strings) = raw.get("strings").and_then(|s| s.as_object()) {
+            for (key, value) in strings {
+                let extraction_state = value
+                    .get("extractionState")
+                    .and_then(|e| e.as_str())
+                    .unwrap_or_default();
+                let should_translate = value
+                    .get("shouldTranslate")
+                    .and_then(|s| s.as_bool())
+                    .unwrap_or(true);
+
+                if (!filter.extraction_states.is_empty()
+                    && !filter
+                        .extraction_states
+                        .iter()
+                        .any(|e| e == extraction_state))
+                    || (filter.skip_should_not_translate && !should_translate)
+                {
+                    excluded_keys.insert(key.clone());
+                }
+            }
+        }
+
+        let previous = match (&filter.changed_since, &filter.changed_since_revision) {
+            (Some(_), Some(_)) => {
+                exit_with_log!("Use either changed_since or changed_since_revision, not both");
+            }
+            (Some(changed_since), None) => {
+                Some(std::fs::read(changed_since).map_err(ConvertError::io(changed_since))?)
+            }
+            (None, Some(revision)) => Some(read_at_revision(path_to_xcstrings, revision)?),
+            (None, None) => None,
+        };
+        let previous_sources = match previous {
+            None => None,
+            Some(previous) => {
+                let (parsed, devices) = crate::devices::parse(&previous)?;
+                let localized_per_language = parsed.localizable.localized_per_language();
+                let base_language = &parsed.translation.source_language;
+                let sources_of = |language: &String| {
+                    rows_with_devices(&localized_per_language, base_language, &devices, language)
+                        .into_iter()
+                        .map(|row| (row.unit_id(), row.base_value))
+                        .collect()
+                };
+
+                Some(
+                    languages
+                        .iter()
+                        .map(|language| (language.clone(), sources_of(language)))
+                        .collect(),
+                )
+            }
+        };
+
+        Ok(Self {
+            only_untranslated: filter.only_untranslated,
+            key_patterns,
+            excluded_keys,
+            previous_sources,
+        })
+    }
+
+    pub(crate) fn has_previous_sources(&self) -> bool {
+        self.previous_sources.is_some()
+    }
+
+    /// The base value of the row of the language in the older catalog, empty if the row was added
+    pub(crate) fn previous_source(&self, row: &Row, language: &str) -> &str {
+        self.previous_sources
+            .as_ref()
+            .and_then(|p| p.get(language)?.get(&row.unit_id()))
+            .map(|p| p.as_str())
+            .unwrap_or_default()
+    }
+
+    /// The row is 1 of the language, the unit ids of plural variates differ per language
+    pub(crate) fn matches(&self, row: &Row, language: &str) -> bool {
+        if self.only_untranslated && !row.translated_value.is_empty() && row.state != NEW_STATE {
+            return false;
+        }
+
+        if let Some(previous_sources) = &self.previous_sources {
+            let previous_source = previous_sources
+                .get(language)
+                .and_then(|p| p.get(&row.unit_id()));
+
+            if previous_source == Some(&row.base_value) {
+                return false;
+            }
+        }
+
+        !self.excluded_keys.contains(&row.key)
+            && self.key_patterns.iter().all(|p| p.is_match(&row.key))
+    }
+}
+
+/// Reads the file from the local repository with git show, the path is relative to the directory of the file
+fn read_at_revision(path: &Path, revision: &str) -> Result<Vec<u8>, ConvertError> {
+    let directory = match path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent,
+        _ => Path::new("."),
+    };
+    let file_name = match path.file_name() {
+        None => {
+            exit_with_log!(format!("Not a file: {:#?}", path));
+        }
+        Some(file_name) => file_name.to_string_lossy(),
+    };
+    let output = Command::new("git")
+        .arg("show")
+        .arg(format!("{revision}:./{file_name}"))
+        .current_dir(directory)
+        .output()
+        .map_err(ConvertError::io(directory))?;
+
+    if !output.status.success() {
+        exit_with_log!(format!(
+            "git show {revision} failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+
+    Ok(output.stdout)
+}
+
+fn glob_to_regex(glob: &str) -> Result<Regex, ConvertError> {
+    let pattern = glob
+        .chars()
+        .map(|c| match c {
+            '*' => ".*".to_string(),
+            '?' => ".".to_string(),
+            c => regex::escape(&c.to_string()),
+        })
+        .collect::<String>();
+
+    Regex::new(&format!("(?s)^{pattern}$"))
+        .map_err(|e| ConvertError::from(format!("Invalid key glob: {e}")))
+}
+
+#[cfg(test)]
+mod test {
+    use crate::docx_writer::config::{Config, Filter};
+    use crate::docx_writer::convert::convert_from_raw;
+    use std::env::current_dir;
+
+    #[test]
+    fn test_filter() {
+        let save_in = current_dir().unwrap().join("generated").join("filter");
+        let mut raw: serde_json::Value =
+            serde_json::from_slice(include_bytes!("../../resources/reader_test_base.xcstrings"))
+                .unwrap();
+
+        raw["strings"]["plurals"]["shouldTranslate"] = false.into();
+
+        let raw = serde_json::to_vec(&raw).unwrap();
+        let convert_languages = |filter: Filter, new_language_codes: Vec<String>| {
+            convert_from_raw(
+                &raw,
+                Config {
+                    save_in: save_in.clone(),
+                    clean_dir_before_generating: true,
+                    new_language_codes,
+                    path_to_xcstrings: Default::default(),
+                    columns_in_output: vec![],
+                    translation_memories: vec![],
+                    suggestion_catalogs: vec![],
+                    suggestion_threshold: 70,
+                    language_groups: vec![],
+                    filter,
+                },
+            )
+            .unwrap()
+        };
+        let convert = |filter: Filter| convert_languages(filter, vec![]).remove(0);
+
+        // normal_0 is translated
+        let export = convert(Filter {
+            only_untranslated: true,
+            key_glob: Some("normal_*".to_string()),
+            ..Default::default()
+        });
+
+        assert_eq!(1, export.amount_keys_to_translate);
+        assert_eq!(8, export.amount_keys_total);
+
+        // Both plural variations are skipped
+        let export = convert(Filter {
+            skip_should_not_translate: true,
+            ..Default::default()
+        });
+
+        assert_eq!(6, export.amount_keys_to_translate);
+
+        // The previous release didn't have normal_1 and had another text for a plural
+        let mut previous: serde_json::Value =
+            serde_json::from_slice(include_bytes!("../../resources/reader_test_base.xcstrings"))
+                .unwrap();
+        let changed_since = save_in.with_extension("xcstrings");
+
+        previous["strings"]
+            .as_object_mut()
+            .unwrap()
+            .remove("normal_1");
+        previous["strings"]["plurals"]["localizations"]["en"]["variations"]["plural"]["one"]
+            ["stringUnit"]["value"] = "%1$lld item".into();
+        std::fs::write(&changed_since, serde_json::to_vec(&previous).unwrap()).unwrap();
+
+        let export = convert(Filter {
+            changed_since: Some(changed_since),
+            ..Default::default()
+        });
+        let extracted =
+            crate::xcstrings_docx_merger::docx_extractor::extract(&save_in.join("nl.docx"))
+                .unwrap()
+                .remove(0);
+
+        assert_eq!(2, export.amount_keys_to_translate);
+        assert_eq!(
+            vec!["normal_1", "plurals"],
+            extracted
+                .extracted
+                .iter()
+                .map(|e| e.key.as_str())
+                .collect::<Vec<_>>()
+        );
+
+        // Nothing changed, also not for the few and many rows of Polish which the base language doesn't have
+        let unchanged = save_in.with_extension("unchanged.xcstrings");
+
+        std::fs::write(&unchanged, &raw).unwrap();
+
+        let exports = convert_languages(
+            Filter {
+                changed_since: Some(unchanged),
+                ..Default::default()
+            },
+            vec!["pl".to_string()],
+        );
+
+        assert!(exports.iter().any(|e| e.language_code == "pl"));
+        assert!(exports.iter().all(|e| e.amount_keys_to_translate == 0));
+    }
+}
@@ -1,24 +1,134 @@
+use crate::xcstrings_docx_merger::validation::PlaceholderProblem;
 use docx_rust::DocxError;
+use serde::ser::SerializeStruct;
+use serde::{Serialize, Serializer};
 use std::error::Error;
 use std::fmt::{Display, Formatter};
+use std::path::{Path, PathBuf};
 use swift_localizable_json_parser::types::output::ParsedError;
 
-#[derive(Debug, Clone)]
+type Source = Box<dyn Error + Send + Sync + 'static>;
+
+#[derive(Debug)]
 pub enum ConvertError {
-    Wrapped(String),
+    /// The key is not in the catalog
+    MissingKey {
+        key: String,
+    },
+    /// The key has a variation, but the catalog has a plain value for it
+    UnexpectedVariation {
+        key: String,
+    },
+    /// The key has no variation, but the catalog has variations for it
+    MissingVariation {
+        key: String,
+    },
+    /// A required column is not in the header row
+    MissingColumn(String),
+    /// There is no table with a translation header in the document
+    MissingTranslationTable,
+    MissingFile {
+        path: PathBuf,
+    },
+    CorruptedDocx {
+        reason: String,
+    },
+    Io {
+        path: Option<PathBuf>,
+        source: std::io::Error,
+    },
+    /// A file could not be parsed, the format is e.g.: xcstrings, json, xml
+    Parse {
+        format: &'static str,
+        source: Source,
+    },
+    /// A file could not be written, the format is e.g.: xlsx
+    Write {
+        format: &'static str,
+        source: Source,
+    },
+    /// Translations break the placeholders of their base value
+    InvalidPlaceholders(Vec<PlaceholderProblem>),
+    /// The content of a file is valid in its format, but not usable
+    Invalid(String),
+    /// An error of 1 of multiple files, the code is the code of the error
+    InFile {
+        path: PathBuf,
+        source: Box<ConvertError>,
+    },
 }
 
 impl ConvertError {
     pub fn corrupted_docx_file() -> Self {
-        ConvertError::from("Corrupted .docx file")
+        ConvertError::CorruptedDocx {
+            reason: "unexpected table content".to_string(),
+        }
+    }
+
+    /// Adds the path to an io error, use it as: `.map_err(ConvertError::io(&path))`
+    pub fn io(path: &Path) -> impl FnOnce(std::io::Error) -> Self + '_ {
+        move |source| ConvertError::Io {
+            path: Some(path.to_path_buf()),
+            source,
+        }
+    }
+
+    /// Adds the file to an error of 1 of multiple files, use it as: `.map_err(ConvertError::in_file(&path))`
+    pub fn in_file(path: &Path) -> impl FnOnce(ConvertError) -> Self + '_ {
+        move |source| ConvertError::InFile {
+            path: path.to_path_buf(),
+            source: Box::new(source),
+        }
+    }
+
+    /// [ConvertError::MissingFile] if an input file or directory doesn't exist, reading it would fail with a less helpful io error
+    pub fn ensure_exists(path: &Path) -> Result<(), Self> {
+        if path.exists() {
+            Ok(())
+        } else {
+            Err(ConvertError::MissingFile {
+                path: path.to_path_buf(),
+            })
+        }
+    }
+
+    /// Stable machine-readable code, part of the CLI output
+    pub fn code(&self) -> &'static str {
+        match self {
+            ConvertError::MissingKey { .. } => "missing_key",
+            ConvertError::UnexpectedVariation { .. } => "unexpected_variation",
+            ConvertError::MissingVariation { .. } => "missing_variation",
+            ConvertError::MissingColumn(_) => "missing_column",
+            ConvertError::MissingTranslationTable => "missing_translation_table",
+            ConvertError::MissingFile { .. } => "missing_file",
+            ConvertError::CorruptedDocx { .. } => "corrupted_docx",
+            ConvertError::Io { .. } => "io",
+            ConvertError::Parse { .. } => "parse",
+            ConvertError::Write { .. } => "write",
+            ConvertError::InvalidPlaceholders(_) => "invalid_placeholders",
+            ConvertError::Invalid(_) => "invalid",
+            ConvertError::InFile { source, .. } => source.code(),
+        }
+    }
+
+    /// This error and all its sources on 1 line
+    pub fn message(&self) -> String {
+        let mut message = self.to_string();
+        let mut source = self.source();
+
+        while let Some(error) = source {
+            message.push_str(": ");
+            message.push_str(&error.to_string());
+            source = error.source();
+        }
+
+        message.replace('\n', " ")
     }
 }
 
 impl From<String> for ConvertError {
     fn from(value: String) -> Self {
-        debug_assert!(false, "{value}");
-
-        ConvertError::Wrapped(value)
+        ConvertError::Invalid(value)
     }
 }
 
@@ -31,48 +141,185 @@ impl From<&str> for ConvertError {
 impl Display for ConvertError {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         match self {
-            // Replace all newlines so it's always on 1 line
-            ConvertError::Wrapped(error) => {
-                write!(f, "Error occurred: {}", error.replace('\n', " "))
+            ConvertError::MissingKey { key } => {
+                write!(f, "There is no matching key for: {key}")
+            }
+            ConvertError::UnexpectedVariation { key } => {
+                write!(f, "Expected no variation for key: {key}")
+            }
+            ConvertError::MissingVariation { key } => {
+                write!(f, "Expected variation for key: {key}")
+            }
+            ConvertError::MissingColumn(name) => write!(f, "There is no {name} column"),
+            ConvertError::MissingTranslationTable => {
+                write!(f, "There is no table with a translation header")
             }
+            ConvertError::MissingFile { path } => {
+                write!(f, "File does not exists at path: {}", path.display())
+            }
+            ConvertError::CorruptedDocx { reason } => {
+                write!(f, "Corrupted .docx file: {reason}")
+            }
+            ConvertError::Io { path: None, .. } => write!(f, "IO error"),
+            ConvertError::Io {
+                path: Some(path), ..
+            } => write!(f, "IO error at path: {}", path.display()),
+            ConvertError::Parse { format, .. } => write!(f, "Invalid {format} file"),
+            ConvertError::Write { format, .. } => write!(f, "Could not write {format} file"),
+            ConvertError::InvalidPlaceholders(problems) => write!(
+                f,
+                "Invalid placeholders for: {}",
+                problems
+                    .iter()
+                    .map(|p| p.to_string())
+                    .collect::<Vec<_>>()
+                    .join("; ")
+            ),
+            ConvertError::Invalid(error) => write!(f, "{error}"),
+            ConvertError::InFile { path, .. } => write!(f, "{}", path.display()),
         }
     }
 }
 
 impl Error for ConvertError {
     fn source(&self) -> Option<&(dyn Error + 'static)> {
-        None
+        match self {
+            ConvertError::Io { source, .. } => Some(source),
+            ConvertError::Parse { source, .. } | ConvertError::Write { source, .. } => {
+                Some(source.as_ref())
+            }
+            ConvertError::InFile { source, .. } => Some(source.as_ref()),
+            _ => None,
+        }
+    }
+}
+
+impl Serialize for ConvertError {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut state = serializer.serialize_struct("ConvertError", 2)?;
+
+        state.serialize_field("code", self.code())?;
+        state.serialize_field("message", &self.message())?;
+        state.end()
     }
 }
 
 impl From<ParsedError> for ConvertError {
     fn from(value: ParsedError) -> Self {
-        debug_assert!(false, "{:#?}", value);
-
-        ConvertError::Wrapped(value.to_string())
+        ConvertError::Parse {
+            format: "xcstrings",
+            source: Box::new(value),
+        }
     }
 }
 
 impl From<std::io::Error> for ConvertError {
     fn from(value: std::io::Error) -> Self {
-        debug_assert!(false);
-
-        ConvertError::Wrapped(value.to_string())
+        ConvertError::Io {
+            path: None,
+            source: value,
+        }
     }
 }
 
 impl From<serde_json::Error> for ConvertError {
     fn from(value: serde_json::Error) -> Self {
-        debug_assert!(false);
-
-        ConvertError::Wrapped(value.to_string())
+        ConvertError::Parse {
+            format: "json",
+            source: Box::new(value),
+        }
     }
 }
 
 impl From<DocxError> for ConvertError {
     fn from(value: DocxError) -> Self {
-        debug_assert!(false);
+        match value {
+            DocxError::IO(source) => ConvertError::from(source),
+            // Both errors are not exposed as std errors by docx-rust
+            DocxError::Xml(error) => ConvertError::CorruptedDocx {
+                reason: format!("{:?}", error),
+            },
+            DocxError::Zip(error) => ConvertError::CorruptedDocx {
+                reason: format!("{:?}", error),
+            },
+        }
+    }
+}
+
+impl From<roxmltree::Error> for ConvertError {
+    fn from(value: roxmltree::Error) -> Self {
+        ConvertError::Parse {
+            format: "xml",
+            source: Box::new(value),
+        }
+    }
+}
+
+impl From<rust_xlsxwriter::XlsxError> for ConvertError {
+    fn from(value: rust_xlsxwriter::XlsxError) -> Self {
+        ConvertError::Write {
+            format: "xlsx",
+            source: Box::new(value),
+        }
+    }
+}
+
+impl From<calamine::XlsxError> for ConvertError {
+    fn from(value: calamine::XlsxError) -> Self {
+        ConvertError::Parse {
+            format: "xlsx",
+            source: Box::new(value),
+        }
+    }
+}
+
+impl From<csv::Error> for ConvertError {
+    fn from(value: csv::Error) -> Self {
+        ConvertError::Parse {
+            format: "csv",
+            source: Box::new(value),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::error::ConvertError;
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_serialize() {
+        let path = PathBuf::from("missing.xcstrings");
+        let error = std::fs::read(&path)
+            .map_err(ConvertError::io(&path))
+            .unwrap_err();
+        let json = serde_json::to_value(&error).unwrap();
+
+        assert_eq!("io", json["code"]);
+        assert!(json["message"]
+            .as_str()
+            .unwrap()
+            .starts_with("IO error at path: missing.xcstrings: "));
+        assert!(std::error::Error::source(&error).is_some());
+
+        let error =
+            crate::xliff_writer::convert::convert_from_path(crate::xliff_writer::config::Config {
+                save_in: PathBuf::from("generated").join("missing"),
+                clean_dir_before_generating: false,
+                new_language_codes: vec![],
+                path_to_xcstrings: path.clone(),
+            })
+            .unwrap_err();
+
+        assert_eq!("missing_file", error.code());
+        assert!(error.message().contains("missing.xcstrings"));
+
+        // The file of a batch is added to the message, the code stays the code of the error
+        let error = ConvertError::in_file(&PathBuf::from("nl.docx"))(error);
 
-        ConvertError::Wrapped(format!("{:#?}", value))
+        assert_eq!("missing_file", error.code());
+        assert!(error
+            .message()
+            .starts_with("nl.docx: File does not exists at path: "));
     }
 }
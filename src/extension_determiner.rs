@@ -4,6 +4,7 @@ use serde::Serialize;
 use swift_localizable_json_parser::types::input::Translation;
 use crate::error::ConvertError;
 use crate::xcstrings_docx_merger::docx_extractor::extract;
+use crate::xcstrings_xliff_merger::xliff_extractor;
 
 #[derive(Clone, Debug, Parser)]
 pub struct Config {
@@ -15,6 +16,7 @@ pub struct Config {
 pub enum Export {
     XCStrings,
     Docx,
+    Xliff,
     Other,
 }
 
@@ -26,7 +28,13 @@ pub fn extension_determiner(config: Config) -> Result<Export, ConvertError> {
         }
     }
 
-    let to_str = std::fs::read_to_string(&config.path_to_file)?;
+    let to_str = std::fs::read_to_string(&config.path_to_file)
+        .map_err(ConvertError::io(&config.path_to_file))?;
+
+    if xliff_extractor::extract_from_raw(&to_str).is_ok() {
+        return Ok(Export::Xliff);
+    }
+
     let translation: serde_json::Result<Translation> = serde_json::from_str(&to_str);
 
     if translation.is_ok() {
@@ -0,0 +1,69 @@
+use crate::rows::Row;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Identifies the document property that holds the fingerprints, translators can't see it in the document itself
+const FINGERPRINTS_PREFIX: &str = "xcstringsdocx-fingerprints:";
+
+/// The base value and translation of a row when it was exported
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RowFingerprint {
+    pub base: String,
+    pub translation: String,
+}
+
+impl RowFingerprint {
+    pub fn from_row(row: &Row) -> Self {
+        Self {
+            base: fingerprint(&row.base_value),
+            translation: fingerprint(&row.translated_value),
+        }
+    }
+}
+
+/// The fingerprints per unit id, see [Row::unit_id]
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct Fingerprints(pub BTreeMap<String, RowFingerprint>);
+
+impl Fingerprints {
+    pub fn from_rows(rows: &[Row]) -> Self {
+        Self(
+            rows.iter()
+                .map(|row| (row.unit_id(), RowFingerprint::from_row(row)))
+                .collect(),
+        )
+    }
+}
+
+/// The fingerprints of a document per language, a document can have multiple target languages
+pub fn to_description(
+    per_language: &BTreeMap<String, Fingerprints>,
+) -> Result<String, serde_json::Error> {
+    Ok(format!(
+        "{FINGERPRINTS_PREFIX}{}",
+        serde_json::to_string(per_language)?
+    ))
+}
+
+/// None if the description doesn't contain fingerprints, e.g. for documents of older versions
+pub fn from_description(description: &str) -> Option<BTreeMap<String, Fingerprints>> {
+    let json = description.trim().strip_prefix(FINGERPRINTS_PREFIX)?;
+
+    match serde_json::from_str(json) {
+        Ok(per_language) => Some(per_language),
+        Err(err) => {
+            log::warn!("Ignoring invalid fingerprints: {err}");
+
+            None
+        }
+    }
+}
+
+/// A 64 bit FNV-1a hash, which is stable across versions unlike the hasher of std
+pub fn fingerprint(text: &str) -> String {
+    let hash = text.bytes().fold(0xcbf29ce484222325u64, |hash, byte| {
+        (hash ^ byte as u64).wrapping_mul(0x100000001b3)
+    });
+
+    format!("{hash:016x}")
+}
@@ -0,0 +1,11 @@
+use clap::Parser;
+use std::path::PathBuf;
+
+#[derive(Clone, Debug, Parser)]
+pub struct Config {
+    /// The path to an existing .xcstrings file to export the localizations from
+    #[clap(long)]
+    pub path_to_xcstrings: PathBuf,
+    #[clap(long)]
+    pub write_in: PathBuf,
+}
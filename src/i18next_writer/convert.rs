@@ -0,0 +1,126 @@
+use crate::docx_writer::convert::Export;
+use crate::error::ConvertError;
+use crate::i18next_writer::config::Config;
+use crate::json::OrderedObject;
+use crate::placeholders::{plural_position, to_named};
+use std::collections::HashMap;
+use swift_localizable_json_parser::types::output::{PluralVariate, Translation};
+
+pub const PLURAL_SUFFIX_SEPARATOR: &str = "_";
+pub const JSON_EXTENSION: &str = "json";
+
+pub fn write_jsons(config: Config) -> Result<Vec<Export>, ConvertError> {
+    ConvertError::ensure_exists(&config.path_to_xcstrings)?;
+
+    let parsed = swift_localizable_json_parser::parse_from_file(&config.path_to_xcstrings)?;
+    let source_language = &parsed.translation.source_language;
+    let plural_positions = parsed
+        .localizable
+        .single_translation
+        .iter()
+        .map(|s| (s.key_raw.as_str(), plural_position(s, source_language)))
+        .collect::<HashMap<_, _>>();
+    let localized_per_language = parsed.localizable.localized_per_language();
+
+    std::fs::create_dir_all(&config.write_in).map_err(ConvertError::io(&config.write_in))?;
+
+    let mut exports = vec![];
+
+    for (language, translations) in &localized_per_language.language_localized {
+        log::debug!("Writing i18next json for language: {language}");
+
+        let mut json = OrderedObject::default();
+        let mut amount_keys = 0;
+
+        for translation in &translations.translations {
+            let name = &translation.key_alphanumeric;
+
+            match &translation.translation {
+                Translation::Localization(l) => {
+                    // i18next falls back to the fallback language for missing keys
+                    if l.value.is_empty() {
+                        continue;
+                    }
+
+                    json.insert(name, to_named(&l.value, None, "{{", "}}"));
+                }
+                Translation::PluralVariation(pv) => {
+                    let plural_position = plural_positions
+                        .get(translation.key_raw.as_str())
+                        .copied()
+                        .flatten();
+                    let pv = pv
+                        .iter()
+                        .filter(|pv| !pv.translation_value.value.is_empty())
+                        .collect::<Vec<_>>();
+
+                    if pv.is_empty() {
+                        continue;
+                    }
+
+                    for pv in pv {
+                        json.insert(
+                            plural_name(name, &pv.variate),
+                            to_named(&pv.translation_value.value, plural_position, "{{", "}}"),
+                        );
+                    }
+                }
+            }
+
+            amount_keys += 1;
+        }
+
+        let file_name = format!("{language}.{JSON_EXTENSION}");
+
+        let path = config.write_in.join(&file_name);
+
+        std::fs::write(&path, json.to_pretty_string()?).map_err(ConvertError::io(&path))?;
+
+        exports.push(Export {
+            amount_keys_to_translate: amount_keys,
+            amount_keys_total: amount_keys,
+            language_code: language.to_string(),
+            file_name,
+        });
+    }
+
+    Ok(exports)
+}
+
+/// The key of a plural form, e.g.: items_one. i18next uses _zero for exactly zero, just like Apple.
+pub fn plural_name(name: &str, variate: &PluralVariate) -> String {
+    format!(
+        "{name}{PLURAL_SUFFIX_SEPARATOR}{}",
+        variate.android_key().to_lowercase()
+    )
+}
+
+#[cfg(test)]
+mod test {
+    use crate::i18next_writer::config::Config;
+    use std::env::current_dir;
+
+    #[test]
+    fn test_write_jsons() {
+        let base = current_dir().unwrap();
+        let write_in = base.join("generated").join("i18next");
+
+        let _ = std::fs::remove_dir_all(&write_in);
+
+        let exports = super::write_jsons(Config {
+            path_to_xcstrings: base.join("resources").join("reader_test_base.xcstrings"),
+            write_in: write_in.clone(),
+        })
+        .unwrap();
+
+        assert_eq!(2, exports.len());
+
+        let en = std::fs::read_to_string(write_in.join("en.json")).unwrap();
+
+        assert!(en.contains("\"plurals_one\": \"{{count}} single\""));
+        assert!(en.contains("\"plurals_other\": \"{{count}} multiple\""));
+        assert!(en.contains(
+            "\"inlined_with_an_inlined_base_language\": \"Keys to localize: {{p1}} ({{p2}}%)\""
+        ));
+    }
+}
@@ -0,0 +1,23 @@
+use crate::error::ConvertError;
+use serde::{Serialize, Serializer};
+use serde_json::Value;
+
+/// A JSON object which keeps the insertion order, e.g.: an ARB key directly followed by its metadata
+#[derive(Clone, Debug, Default)]
+pub struct OrderedObject(pub Vec<(String, Value)>);
+
+impl OrderedObject {
+    pub fn insert(&mut self, key: impl Into<String>, value: impl Into<Value>) {
+        self.0.push((key.into(), value.into()));
+    }
+
+    pub fn to_pretty_string(&self) -> Result<String, ConvertError> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+}
+
+impl Serialize for OrderedObject {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_map(self.0.iter().map(|(k, v)| (k, v)))
+    }
+}
@@ -0,0 +1,15 @@
+use clap::Parser;
+use std::path::PathBuf;
+
+#[derive(Clone, Debug, Parser)]
+pub struct Config {
+    /// The path to an existing .xcstrings file to export the localizations from
+    #[clap(long)]
+    pub path_to_xcstrings: PathBuf,
+    /// The directory to write the *.lproj directories in
+    #[clap(long)]
+    pub save_in: PathBuf,
+    /// The name of the .strings and .stringsdict files, without extension
+    #[clap(long, default_value = "Localizable")]
+    pub table_name: String,
+}
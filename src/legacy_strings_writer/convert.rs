@@ -0,0 +1,204 @@
+use crate::error::ConvertError;
+use crate::legacy_strings_writer::config::Config;
+use crate::xcstrings_legacy_strings_merger::convert::LPROJ_EXTENSION;
+use crate::xcstrings_legacy_strings_merger::strings_extractor::FORMAT_KEY;
+use crate::xml::escape;
+use regex::Regex;
+use serde::Serialize;
+use std::sync::LazyLock;
+use swift_localizable_json_parser::types::output::{SinglePluralVariation, Translation};
+
+/// The variable every plural value is substituted in
+const PLURAL_VARIABLE: &str = "value";
+const DEFAULT_FORMAT_VALUE_TYPE: &str = "d";
+
+#[derive(Clone, Debug, Serialize)]
+pub struct Export {
+    pub written_lprojs: Vec<WrittenLproj>,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct WrittenLproj {
+    pub language_code: String,
+    pub dir_name: String,
+    pub amount_strings: i32,
+    pub amount_plurals: i32,
+}
+
+pub fn write_strings(config: Config) -> Result<Export, ConvertError> {
+    ConvertError::ensure_exists(&config.path_to_xcstrings)?;
+
+    let localized_per_language =
+        swift_localizable_json_parser::parse_from_file(&config.path_to_xcstrings)?
+            .localizable
+            .localized_per_language();
+    let mut languages = localized_per_language
+        .language_localized
+        .keys()
+        .cloned()
+        .collect::<Vec<_>>();
+
+    languages.sort();
+
+    let mut written_lprojs = vec![];
+
+    for language in languages {
+        log::debug!("Writing .strings for language: {language}");
+
+        let mut strings = String::new();
+        let mut plurals = String::new();
+        let mut amount_strings = 0;
+        let mut amount_plurals = 0;
+
+        for translation in &localized_per_language.language_localized[&language].translations {
+            match &translation.translation {
+                Translation::Localization(l) => {
+                    if l.value.is_empty() {
+                        continue;
+                    }
+
+                    if !translation.comment.is_empty() {
+                        strings += &format!("/* {} */\n", translation.comment.replace("*/", "* /"));
+                    }
+
+                    strings += &format!(
+                        "\"{}\" = \"{}\";\n\n",
+                        escape_strings(&translation.key_raw),
+                        escape_strings(&l.value)
+                    );
+                    amount_strings += 1;
+                }
+                Translation::PluralVariation(pv) => {
+                    let pv = pv
+                        .iter()
+                        .filter(|pv| !pv.translation_value.value.is_empty())
+                        .collect::<Vec<_>>();
+
+                    if pv.is_empty() {
+                        continue;
+                    }
+
+                    plurals += &stringsdict_entry(&translation.key_raw, &pv);
+                    amount_plurals += 1;
+                }
+            }
+        }
+
+        let dir_name = format!("{language}.{LPROJ_EXTENSION}");
+        let lproj = config.save_in.join(&dir_name);
+
+        std::fs::create_dir_all(&lproj).map_err(ConvertError::io(&lproj))?;
+
+        if amount_strings > 0 {
+            let path = lproj.join(format!("{}.strings", config.table_name));
+
+            std::fs::write(&path, strings).map_err(ConvertError::io(&path))?;
+        }
+
+        if amount_plurals > 0 {
+            let stringsdict = format!(
+                "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
+                <!DOCTYPE plist PUBLIC \"-//Apple//DTD PLIST 1.0//EN\" \"http://www.apple.com/DTDs/PropertyList-1.0.dtd\">\n\
+                <plist version=\"1.0\">\n<dict>\n{plurals}</dict>\n</plist>\n"
+            );
+
+            let path = lproj.join(format!("{}.stringsdict", config.table_name));
+
+            std::fs::write(&path, stringsdict).map_err(ConvertError::io(&path))?;
+        }
+
+        written_lprojs.push(WrittenLproj {
+            language_code: language,
+            dir_name,
+            amount_strings,
+            amount_plurals,
+        });
+    }
+
+    Ok(Export { written_lprojs })
+}
+
+fn escape_strings(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+        .replace('\t', "\\t")
+}
+
+/// The type of the number of a plural, from its format specifier
+static VALUE_TYPE_REGEX: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"%(?:\d+\$)?(l{0,2}[diuf])").unwrap());
+
+fn stringsdict_entry(key: &str, variations: &[&SinglePluralVariation]) -> String {
+    // The plural rule needs the type of the number, take it from the first format specifier
+    let value_type = variations
+        .iter()
+        .find_map(|pv| VALUE_TYPE_REGEX.captures(&pv.translation_value.value))
+        .map(|c| c[1].to_string())
+        .unwrap_or(DEFAULT_FORMAT_VALUE_TYPE.to_string());
+    let mut entry = format!(
+        "\t<key>{}</key>\n\t<dict>\n\t\t<key>{FORMAT_KEY}</key>\n\t\t<string>%#@{PLURAL_VARIABLE}@</string>\n\
+        \t\t<key>{PLURAL_VARIABLE}</key>\n\t\t<dict>\n\
+        \t\t\t<key>NSStringFormatSpecTypeKey</key>\n\t\t\t<string>NSStringPluralRuleType</string>\n\
+        \t\t\t<key>NSStringFormatValueTypeKey</key>\n\t\t\t<string>{value_type}</string>\n",
+        escape(key)
+    );
+
+    for pv in variations {
+        entry += &format!(
+            "\t\t\t<key>{}</key>\n\t\t\t<string>{}</string>\n",
+            pv.variate.android_key().to_lowercase(),
+            escape(&pv.translation_value.value)
+        );
+    }
+
+    entry += "\t\t</dict>\n\t</dict>\n";
+
+    entry
+}
+
+#[cfg(test)]
+mod test {
+    use crate::legacy_strings_writer::config::Config;
+    use crate::legacy_strings_writer::convert::write_strings;
+    use crate::xcstrings_legacy_strings_merger::strings_extractor::{
+        extract_strings, extract_stringsdict,
+    };
+    use std::env::current_dir;
+
+    #[test]
+    fn test_write_strings() {
+        let base = current_dir().unwrap();
+        let save_in = base.join("generated").join("legacy_strings");
+
+        let _ = std::fs::remove_dir_all(&save_in);
+
+        let export = write_strings(Config {
+            path_to_xcstrings: base.join("resources").join("reader_test_base.xcstrings"),
+            save_in: save_in.clone(),
+            table_name: "Localizable".to_string(),
+        })
+        .unwrap();
+
+        assert!(export
+            .written_lprojs
+            .iter()
+            .any(|w| w.dir_name == "nl.lproj"));
+
+        let nl = save_in.join("nl.lproj");
+        let strings = extract_strings(&nl.join("Localizable.strings")).unwrap();
+        let newlines = strings
+            .iter()
+            .find(|s| s.key == "Inlined\nwith \n\n newlines")
+            .unwrap();
+
+        assert_eq!("Ingelijnd\nmet \n\n newlines", newlines.value);
+
+        let (plurals, unsupported) =
+            extract_stringsdict(&nl.join("Localizable.stringsdict")).unwrap();
+
+        assert!(unsupported.is_empty());
+        assert!(!plurals.is_empty());
+    }
+}
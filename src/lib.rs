@@ -1,20 +1,55 @@
 #[macro_export]
 macro_rules! exit_with_log {
     ($log: expr) => {{
-        debug_assert!(false, "{}", $log.to_string());
-
-        return Err($crate::error::ConvertError::Wrapped($log.to_string()));
+        return Err($crate::error::ConvertError::Invalid($log.to_string()));
     }};
 }
 
 pub mod android_xml_writer;
+pub mod arb_writer;
+pub mod backup;
+pub mod csv_writer;
+pub mod devices;
 pub mod docx_metadata;
 pub mod docx_writer;
 pub mod error;
+pub mod xcstrings_docx_batch_merger;
 pub mod xcstrings_docx_merger;
 pub mod xcstrings_metadata;
 pub mod extension_determiner;
+pub mod fingerprint;
+pub mod i18next_writer;
+mod json;
+pub mod legacy_strings_writer;
+pub mod placeholders;
+pub mod plural_rules;
+pub mod po_writer;
+pub mod rows;
+pub mod tmx_writer;
+pub mod translation_memory;
+pub mod xcstrings_android_xml_merger;
+pub mod xcstrings_arb_merger;
+pub mod xcstrings_csv_merger;
+pub mod xcstrings_i18next_merger;
+pub mod xcstrings_legacy_strings_merger;
+pub mod xcstrings_lint;
+pub mod xcstrings_po_merger;
+pub mod xcstrings_restore;
+pub mod xcloc_writer;
+pub mod xcstrings_xcloc_merger;
+pub mod xcstrings_xlsx_merger;
+pub mod xcstrings_xliff_merger;
+pub mod xliff_writer;
+pub mod xlsx_writer;
+mod xml;
 
 const KEY_KEY: &str = "Key";
 const KEY_VARIATION: &str = "Variation";
 const KEY_COMMENT: &str = "Comment";
+const KEY_STATE: &str = "State";
+const KEY_SUGGESTION: &str = "Suggestion";
+const KEY_PREVIOUS_SOURCE: &str = "Previous source";
+const NEEDS_REVIEW_STATE: &str = "needs_review";
+const STALE_STATE: &str = "stale";
+/// Not an .xcstrings state, marks translations which are pre-filled from a translation memory
+const PREFILLED_STATE: &str = "prefilled";
@@ -1,7 +1,10 @@
 use clap::{Parser, Subcommand};
 use std::process::exit;
 use xcstringsdocx::{
-    android_xml_writer, docx_metadata, docx_writer, xcstrings_docx_merger, xcstrings_metadata, extension_determiner
+    android_xml_writer, arb_writer, csv_writer, docx_metadata, docx_writer, xcstrings_docx_batch_merger, xcstrings_docx_merger, xcstrings_metadata, extension_determiner,
+    i18next_writer, legacy_strings_writer, po_writer, tmx_writer, xcloc_writer, xcstrings_android_xml_merger, xcstrings_arb_merger, xcstrings_csv_merger,
+    xcstrings_i18next_merger, xcstrings_legacy_strings_merger, xcstrings_lint, xcstrings_po_merger, xcstrings_restore, xcstrings_xcloc_merger, xcstrings_xliff_merger,
+    xcstrings_xlsx_merger, xliff_writer, xlsx_writer,
 };
 
 #[derive(Parser, Debug)]
@@ -18,7 +21,7 @@ fn main() {
 
     macro_rules! handle_result {
         ($result: expr) => {{
-            match serde_json::to_string(&$result.map_err(|e| e.to_string())) {
+            match serde_json::to_string(&$result) {
                 Ok(ok) => println!("{ok}"),
                 Err(e) => {
                     eprint!("This is bad: {:#?}", e);
@@ -33,6 +36,9 @@ fn main() {
         ConfigContainer::XCStringsDocxMerger(c) => {
             handle_result!(xcstrings_docx_merger::convert::merge(c))
         }
+        ConfigContainer::XCStringsDocxBatchMerger(c) => {
+            handle_result!(xcstrings_docx_batch_merger::convert::merge(c))
+        }
         ConfigContainer::DocxFromXCStrings(c) => {
             handle_result!(docx_writer::convert::convert_from_path(c))
         }
@@ -48,6 +54,74 @@ fn main() {
         ConfigContainer::ExtensionDeterminer(c) => {
             handle_result!(extension_determiner::extension_determiner(c))
         }
+        ConfigContainer::XliffFromXCStrings(c) => {
+            handle_result!(xliff_writer::convert::convert_from_path(c))
+        }
+        ConfigContainer::XCStringsXliffMerger(c) => {
+            handle_result!(xcstrings_xliff_merger::convert::merge(c))
+        }
+        ConfigContainer::XclocFromXCStrings(c) => {
+            handle_result!(xcloc_writer::convert::convert_from_path(c))
+        }
+        ConfigContainer::XCStringsXclocMerger(c) => {
+            handle_result!(xcstrings_xcloc_merger::convert::merge(c))
+        }
+        ConfigContainer::XlsxFromXCStrings(c) => {
+            handle_result!(xlsx_writer::convert::convert_from_path(c))
+        }
+        ConfigContainer::XCStringsXlsxMerger(c) => {
+            handle_result!(xcstrings_xlsx_merger::convert::merge(c))
+        }
+        ConfigContainer::CsvFromXCStrings(c) => {
+            handle_result!(csv_writer::convert::convert_from_path(c))
+        }
+        ConfigContainer::XCStringsCsvMerger(c) => {
+            handle_result!(xcstrings_csv_merger::convert::merge(c))
+        }
+        ConfigContainer::XCStringsAndroidXmlMerger(c) => {
+            handle_result!(xcstrings_android_xml_merger::convert::merge(c))
+        }
+        ConfigContainer::XCStringsLegacyStringsMerger(c) => {
+            handle_result!(xcstrings_legacy_strings_merger::convert::merge(c))
+        }
+        ConfigContainer::LegacyStringsFromXCStrings(c) => {
+            handle_result!(legacy_strings_writer::convert::write_strings(c))
+        }
+        ConfigContainer::PoFromXCStrings(c) => {
+            handle_result!(po_writer::convert::convert_from_path(c))
+        }
+        ConfigContainer::XCStringsPoMerger(c) => {
+            handle_result!(xcstrings_po_merger::convert::merge(c))
+        }
+        ConfigContainer::ArbWriter(c) => {
+            handle_result!(arb_writer::convert::write_arbs(c))
+        }
+        ConfigContainer::XCStringsArbMerger(c) => {
+            handle_result!(xcstrings_arb_merger::convert::merge(c))
+        }
+        ConfigContainer::I18nextWriter(c) => {
+            handle_result!(i18next_writer::convert::write_jsons(c))
+        }
+        ConfigContainer::XCStringsI18nextMerger(c) => {
+            handle_result!(xcstrings_i18next_merger::convert::merge(c))
+        }
+        ConfigContainer::TmxFromXCStrings(c) => {
+            handle_result!(tmx_writer::convert::write_tmx(c))
+        }
+        ConfigContainer::Restore(c) => {
+            handle_result!(xcstrings_restore::convert::restore(c))
+        }
+        ConfigContainer::Lint(c) => {
+            let result = xcstrings_lint::check::lint(c);
+            // CI fails on any issue
+            let failed = !matches!(&result, Ok(export) if export.issue_count == 0);
+
+            handle_result!(result);
+
+            if failed {
+                exit(1)
+            }
+        }
     };
 }
 
@@ -56,7 +130,28 @@ enum ConfigContainer {
     DocxMetadata(docx_metadata::config::Config),
     XCStringsMetadata(xcstrings_metadata::config::Config),
     XCStringsDocxMerger(xcstrings_docx_merger::config::Config),
+    XCStringsDocxBatchMerger(xcstrings_docx_batch_merger::config::Config),
     DocxFromXCStrings(docx_writer::config::Config),
     AndroidXmlWriter(android_xml_writer::config::Config),
     ExtensionDeterminer(extension_determiner::Config),
+    XliffFromXCStrings(xliff_writer::config::Config),
+    XCStringsXliffMerger(xcstrings_xliff_merger::config::Config),
+    XclocFromXCStrings(xcloc_writer::config::Config),
+    XCStringsXclocMerger(xcstrings_xcloc_merger::config::Config),
+    XlsxFromXCStrings(xlsx_writer::config::Config),
+    XCStringsXlsxMerger(xcstrings_xlsx_merger::config::Config),
+    CsvFromXCStrings(csv_writer::config::Config),
+    XCStringsCsvMerger(xcstrings_csv_merger::config::Config),
+    XCStringsAndroidXmlMerger(xcstrings_android_xml_merger::config::Config),
+    XCStringsLegacyStringsMerger(xcstrings_legacy_strings_merger::config::Config),
+    LegacyStringsFromXCStrings(legacy_strings_writer::config::Config),
+    PoFromXCStrings(po_writer::config::Config),
+    XCStringsPoMerger(xcstrings_po_merger::config::Config),
+    ArbWriter(arb_writer::config::Config),
+    XCStringsArbMerger(xcstrings_arb_merger::config::Config),
+    I18nextWriter(i18next_writer::config::Config),
+    XCStringsI18nextMerger(xcstrings_i18next_merger::config::Config),
+    TmxFromXCStrings(tmx_writer::config::Config),
+    Restore(xcstrings_restore::config::Config),
+    Lint(xcstrings_lint::config::Config),
 }
@@ -0,0 +1,466 @@
+use regex::{Captures, Regex};
+use serde::Serialize;
+use std::borrow::Cow;
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::{Display, Formatter};
+use std::sync::LazyLock;
+use swift_localizable_json_parser::types::output::{SingleTranslation, Translation};
+
+/// The name of the plural number, i18next only selects a plural form for this name
+pub const PLURAL_PLACEHOLDER: &str = "count";
+const PLACEHOLDER_PREFIX: &str = "p";
+
+/// A printf style format specifier, e.g.: %1$lld or %@
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Specifier {
+    pub position: usize,
+    pub specifier: String,
+    pub is_positional: bool,
+}
+
+impl Specifier {
+    pub fn is_integer(&self) -> bool {
+        self.specifier.ends_with(['d', 'i', 'u', 'o', 'x', 'X'])
+    }
+
+    /// The length modifier and conversion, without flags, width and precision, e.g.: lld for %1$-5lld
+    pub fn conversion(&self) -> &str {
+        self.specifier
+            .trim_start_matches(|c: char| !c.is_ascii_alphabetic() && c != '@')
+    }
+
+    /// The placeholder type Flutter expects in the ARB metadata
+    pub fn placeholder_type(&self) -> &'static str {
+        if self.is_integer() {
+            "int"
+        } else if self
+            .specifier
+            .ends_with(['f', 'F', 'e', 'E', 'g', 'G', 'a', 'A'])
+        {
+            "double"
+        } else {
+            "String"
+        }
+    }
+}
+
+const SPECIFIER_PATTERN: &str =
+    r"%(?:(\d+)\$)?([-+0#]*\d*(?:\.\d+)?(?:hh|h|ll|l|q|z|t|j)?[@diouxXcCsSpaAeEfFgG]|%)";
+
+static SPECIFIER_REGEX: LazyLock<Regex> = LazyLock::new(|| Regex::new(SPECIFIER_PATTERN).unwrap());
+/// A specifier or an escaped newline, see [split_specifiers]
+static SPECIFIER_OR_NEWLINE_REGEX: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(&format!(r"\\n|{SPECIFIER_PATTERN}")).unwrap());
+/// The named placeholders of ARB and i18next, see [from_named]
+static CURLY_NAMED_REGEX: LazyLock<Regex> = LazyLock::new(|| named_regex("{", "}"));
+static DOUBLE_CURLY_NAMED_REGEX: LazyLock<Regex> = LazyLock::new(|| named_regex("{{", "}}"));
+
+fn named_regex(open: &str, close: &str) -> Regex {
+    Regex::new(&format!(
+        r"{}\s*(\w+)\s*{}",
+        regex::escape(open),
+        regex::escape(close)
+    ))
+    .unwrap()
+}
+
+/// Values without specifiers aren't format strings, a % in it is taken literally
+pub fn is_format_string(value: &str) -> bool {
+    !specifiers(value).is_empty() || value.contains("%%")
+}
+
+/// Splits a value in text and specifiers (including %%) or escaped newlines (`\n`), the parts joined are the value.
+/// The specifiers are only split off of format strings, see [is_format_string].
+pub fn split_specifiers(value: &str, is_format_string: bool) -> Vec<(&str, bool)> {
+    let mut parts = vec![];
+    let mut last = 0;
+
+    for m in SPECIFIER_OR_NEWLINE_REGEX.find_iter(value) {
+        let is_specifier = m.as_str().starts_with('%');
+
+        if is_specifier && !is_format_string {
+            continue;
+        }
+
+        if m.start() > last {
+            parts.push((&value[last..m.start()], false));
+        }
+
+        parts.push((m.as_str(), true));
+        last = m.end();
+    }
+
+    if last < value.len() || parts.is_empty() {
+        parts.push((&value[last..], false));
+    }
+
+    parts
+}
+
+/// The specifiers of a value, without %%
+pub fn specifiers(value: &str) -> Vec<Specifier> {
+    let mut next_position = 1;
+
+    SPECIFIER_REGEX
+        .captures_iter(value)
+        .filter(|c| &c[2] != "%")
+        .map(|c| specifier_of(&c, &mut next_position))
+        .collect()
+}
+
+fn specifier_of(captures: &Captures, next_position: &mut usize) -> Specifier {
+    let explicit_position = captures.get(1).and_then(|p| p.as_str().parse().ok());
+    let position = explicit_position.unwrap_or_else(|| {
+        *next_position += 1;
+
+        *next_position - 1
+    });
+
+    Specifier {
+        position,
+        specifier: captures[2].to_string(),
+        is_positional: explicit_position.is_some(),
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PlaceholderIssueKind {
+    /// A specifier of the base value is not in the translation
+    Missing,
+    /// The translation has a specifier the base value doesn't have
+    Extra,
+    /// The specifier of the same argument has another type, e.g.: %@ instead of %lld
+    TypeMismatch,
+    /// The arguments are reordered without positional specifiers, e.g.: %@ %lld instead of %2$@ %1$lld
+    Reordered,
+    /// A % which is not a specifier, e.g.: % @ or a single % instead of %%
+    Malformed,
+}
+
+/// A broken placeholder of a translation, compared to the base value
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct PlaceholderIssue {
+    pub kind: PlaceholderIssueKind,
+    pub expected: Option<String>,
+    pub found: Option<String>,
+}
+
+impl Display for PlaceholderIssue {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match (&self.expected, &self.found) {
+            (Some(expected), Some(found)) => {
+                write!(f, "{:?} (expected {expected}, found {found})", self.kind)
+            }
+            (Some(expected), None) => write!(f, "{:?} {expected}", self.kind),
+            (None, Some(found)) => write!(f, "{:?} {found}", self.kind),
+            (None, None) => write!(f, "{:?}", self.kind),
+        }
+    }
+}
+
+/// Compares the specifiers of a translation with the base value.
+/// Values without specifiers aren't format strings, so they never have issues, like empty translations.
+pub fn validate_placeholders(base_value: &str, translated: &str) -> Vec<PlaceholderIssue> {
+    if translated.is_empty() || !is_format_string(base_value) {
+        return vec![];
+    }
+
+    let escaped_percentages = |value: &str| value.matches("%%").count();
+
+    let issue = |kind, expected: Option<&str>, found: Option<&str>| PlaceholderIssue {
+        kind,
+        expected: expected.map(|e| e.to_string()),
+        found: found.map(|f| f.to_string()),
+    };
+    let mut issues = vec![];
+    let mut covered = vec![false; translated.len()];
+
+    for m in SPECIFIER_REGEX.find_iter(translated) {
+        covered[m.range()].iter_mut().for_each(|c| *c = true);
+    }
+
+    for (index, _) in translated.match_indices('%') {
+        // A percentage in the text, e.g.: 100% sure
+        let is_literal = translated[index + 1..]
+            .chars()
+            .next()
+            .is_none_or(char::is_whitespace);
+
+        if !covered[index] && !is_literal {
+            let found = translated[index..].chars().take(3).collect::<String>();
+
+            issues.push(issue(PlaceholderIssueKind::Malformed, None, Some(&found)));
+        }
+    }
+
+    if escaped_percentages(translated) < escaped_percentages(base_value) {
+        issues.push(issue(PlaceholderIssueKind::Missing, Some("%%"), None));
+    }
+
+    let per_position = |value: &str| {
+        specifiers(value)
+            .into_iter()
+            .map(|s| (s.position, s))
+            .collect::<BTreeMap<_, _>>()
+    };
+    let base = per_position(base_value);
+    let translation = per_position(translated);
+    let mut mismatches = vec![];
+
+    for (position, expected) in &base {
+        match translation.get(position) {
+            None => issues.push(issue(
+                PlaceholderIssueKind::Missing,
+                Some(&format!("%{}", expected.specifier)),
+                None,
+            )),
+            Some(found) if found.conversion() != expected.conversion() => {
+                mismatches.push((expected, found))
+            }
+            Some(_) => {}
+        }
+    }
+
+    for (position, found) in &translation {
+        if !base.contains_key(position) {
+            issues.push(issue(
+                PlaceholderIssueKind::Extra,
+                None,
+                Some(&format!("%{}", found.specifier)),
+            ));
+        }
+    }
+
+    let conversions = |specifiers: &BTreeMap<usize, Specifier>| {
+        let mut conversions = specifiers
+            .values()
+            .map(|s| s.conversion().to_string())
+            .collect::<Vec<_>>();
+
+        conversions.sort();
+        conversions
+    };
+    let is_reordered = !mismatches.is_empty()
+        && conversions(&base) == conversions(&translation)
+        && translation.values().any(|s| !s.is_positional);
+
+    if is_reordered {
+        issues.push(issue(PlaceholderIssueKind::Reordered, None, None));
+    } else {
+        for (expected, found) in mismatches {
+            issues.push(issue(
+                PlaceholderIssueKind::TypeMismatch,
+                Some(&format!("%{}", expected.specifier)),
+                Some(&format!("%{}", found.specifier)),
+            ));
+        }
+    }
+
+    issues
+}
+
+/// The position of the number which selects the plural form: the first integer of the base plural values, starting at other
+pub fn plural_position(
+    single_translation: &SingleTranslation,
+    source_language: &str,
+) -> Option<usize> {
+    match single_translation
+        .localization_value
+        .language_translation
+        .get(source_language)?
+    {
+        Translation::Localization(_) => None,
+        Translation::PluralVariation(pv) => pv
+            .iter()
+            .rev()
+            .flat_map(|pv| specifiers(&pv.translation_value.value))
+            .find(|s| s.is_integer())
+            .map(|s| s.position),
+    }
+}
+
+pub fn placeholder_name(position: usize, plural_position: Option<usize>) -> String {
+    if Some(position) == plural_position {
+        PLURAL_PLACEHOLDER.to_string()
+    } else {
+        format!("{PLACEHOLDER_PREFIX}{position}")
+    }
+}
+
+/// Replaces the specifiers with named placeholders, e.g.: %1$lld -> {p1} or {{p1}}.
+/// Values without specifiers aren't format strings, a % in it is taken literally.
+pub fn to_named(value: &str, plural_position: Option<usize>, open: &str, close: &str) -> String {
+    if specifiers(value).is_empty() {
+        return value.to_string();
+    }
+
+    let mut next_position = 1;
+
+    SPECIFIER_REGEX
+        .replace_all(value, |c: &Captures| {
+            if &c[2] == "%" {
+                return "%".to_string();
+            }
+
+            let specifier = specifier_of(c, &mut next_position);
+
+            format!(
+                "{open}{}{close}",
+                placeholder_name(specifier.position, plural_position)
+            )
+        })
+        .to_string()
+}
+
+/// Reverts [to_named], the specifiers are taken from the base value
+pub fn from_named(
+    value: &str,
+    base_value: &str,
+    plural_position: Option<usize>,
+    open: &str,
+    close: &str,
+) -> String {
+    let base_specifiers = specifiers(base_value);
+
+    if base_specifiers.is_empty() {
+        return value.to_string();
+    }
+
+    let regex = match (open, close) {
+        ("{", "}") => Cow::Borrowed(&*CURLY_NAMED_REGEX),
+        ("{{", "}}") => Cow::Borrowed(&*DOUBLE_CURLY_NAMED_REGEX),
+        _ => Cow::Owned(named_regex(open, close)),
+    };
+    let position_of = |name: &str| {
+        if name == PLURAL_PLACEHOLDER {
+            plural_position
+        } else {
+            name.strip_prefix(PLACEHOLDER_PREFIX)?.parse().ok()
+        }
+    };
+    let specifier_per_position = base_specifiers
+        .iter()
+        .map(|s| (s.position, s.specifier.as_str()))
+        .collect::<HashMap<_, _>>();
+    let positions = regex
+        .captures_iter(value)
+        .filter_map(|c| position_of(&c[1]))
+        .collect::<Vec<_>>();
+    // Translators may reorder the placeholders, which only works with positional specifiers
+    let is_positional = base_specifiers.iter().any(|s| s.is_positional)
+        || positions
+            .iter()
+            .enumerate()
+            .any(|(index, p)| index + 1 != *p);
+
+    regex
+        .replace_all(&value.replace('%', "%%"), |c: &Captures| {
+            let position = match position_of(&c[1]) {
+                None => return c[0].to_string(),
+                Some(position) => position,
+            };
+            let specifier = specifier_per_position.get(&position).unwrap_or(&"@");
+
+            if is_positional {
+                format!("%{position}${specifier}")
+            } else {
+                format!("%{specifier}")
+            }
+        })
+        .to_string()
+}
+
+#[cfg(test)]
+mod test {
+    use super::{
+        from_named, specifiers, split_specifiers, to_named, validate_placeholders,
+        PlaceholderIssueKind,
+    };
+
+    #[test]
+    fn test_named() {
+        let base = "Keys to localize: %1$lld (%2$lld%%)";
+        let named = to_named(base, Some(1), "{", "}");
+
+        assert_eq!("Keys to localize: {count} ({p2}%)", named);
+        assert_eq!(base, from_named(&named, base, Some(1), "{", "}"));
+        assert_eq!(
+            "%@ and %@",
+            from_named("{{p1}} and {{p2}}", "%@ and %@", None, "{{", "}}")
+        );
+        assert_eq!(
+            "%2$@ en %1$@",
+            from_named("{{p2}} en {{p1}}", "%@ and %@", None, "{{", "}}")
+        );
+        assert_eq!("Save 100%!", to_named("Save 100%!", None, "{", "}"));
+    }
+
+    #[test]
+    fn test_split_specifiers() {
+        assert_eq!(
+            vec![
+                ("Keys: ", false),
+                ("%1$lld", true),
+                (" (", false),
+                ("%2$lld", true),
+                ("%%", true),
+                (")", false),
+                ("\\n", true),
+            ],
+            split_specifiers("Keys: %1$lld (%2$lld%%)\\n", true)
+        );
+        assert_eq!(
+            vec![("Save 100%!", false)],
+            split_specifiers("Save 100%!", false)
+        );
+        assert_eq!(vec![("", false)], split_specifiers("", false));
+    }
+
+    #[test]
+    fn test_validate_placeholders() {
+        let kinds = |base: &str, translated: &str| {
+            validate_placeholders(base, translated)
+                .into_iter()
+                .map(|i| i.kind)
+                .collect::<Vec<_>>()
+        };
+        let base = "Keys to localize: %1$lld (%2$lld%%)";
+
+        assert!(kinds(base, "Te vertalen: %1$lld (%2$lld%%)").is_empty());
+        assert!(kinds(base, "(%2$lld%%) %1$lld te vertalen").is_empty());
+        assert_eq!(
+            vec![PlaceholderIssueKind::Missing],
+            kinds(base, "Te vertalen: (%2$lld%%)")
+        );
+        assert_eq!(
+            vec![
+                PlaceholderIssueKind::Malformed,
+                PlaceholderIssueKind::Missing
+            ],
+            kinds(base, "Te vertalen: %1$lld (%2$lld%)")
+        );
+        assert_eq!(
+            vec![PlaceholderIssueKind::Missing],
+            kinds("Hello %@", "Hallo % @")
+        );
+        // The space flag is not supported, so a percentage in the text is not a specifier
+        assert!(kinds("%@ is 100% sure", "%@ is 100% zeker").is_empty());
+        assert!(kinds("100% sure, %@", "%@, 100% sure").is_empty());
+        assert!(specifiers("100% sure").is_empty());
+        assert_eq!(
+            vec![PlaceholderIssueKind::Extra],
+            kinds("Hello %@", "Hallo %@ %@")
+        );
+        assert_eq!(
+            vec![PlaceholderIssueKind::TypeMismatch],
+            kinds("%lld items", "%@ items")
+        );
+        assert_eq!(
+            vec![PlaceholderIssueKind::Reordered],
+            kinds("%@ has %lld items", "%lld items of %@")
+        );
+        assert!(kinds("Save 100%!", "Bespaar 100%!").is_empty());
+        assert!(kinds(base, "").is_empty());
+    }
+}
@@ -0,0 +1,297 @@
+use swift_localizable_json_parser::types::output::PluralVariate;
+use swift_localizable_json_parser::types::output::PluralVariate::{
+    Few, Many, One, Other, Two, Zero,
+};
+
+const OTHER: &[PluralVariate] = &[Other];
+const ONE_OTHER: &[PluralVariate] = &[One, Other];
+const ZERO_ONE_OTHER: &[PluralVariate] = &[Zero, One, Other];
+const ONE_TWO_OTHER: &[PluralVariate] = &[One, Two, Other];
+const ONE_FEW_OTHER: &[PluralVariate] = &[One, Few, Other];
+const ONE_MANY_OTHER: &[PluralVariate] = &[One, Many, Other];
+const ONE_TWO_FEW_OTHER: &[PluralVariate] = &[One, Two, Few, Other];
+const ONE_FEW_MANY_OTHER: &[PluralVariate] = &[One, Few, Many, Other];
+const ONE_TWO_FEW_MANY_OTHER: &[PluralVariate] = &[One, Two, Few, Many, Other];
+const ALL: &[PluralVariate] = &[Zero, One, Two, Few, Many, Other];
+
+/// The cardinal plural categories of the CLDR plural rules (version 44) per language
+const RULES: &[(&[&str], &[PluralVariate])] = &[
+    (
+        &[
+            "bm", "bo", "dz", "hnj", "id", "ig", "ii", "in", "ja", "jbo", "jv", "jw", "kde", "kea",
+            "km", "ko", "lkt", "lo", "ms", "my", "nqo", "osa", "sah", "ses", "sg", "su", "th",
+            "to", "tpi", "vi", "wo", "yo", "yue", "zh",
+        ],
+        OTHER,
+    ),
+    (
+        &[
+            "af", "ak", "am", "an", "as", "asa", "ast", "az", "bal", "bem", "bez", "bg", "bho",
+            "bn", "brx", "ce", "ceb", "cgg", "chr", "ckb", "da", "de", "doc", "dv", "ee", "el",
+            "en", "eo", "et", "eu", "fa", "ff", "fi", "fil", "fo", "fur", "fy", "gl", "gsw", "gu",
+            "guw", "ha", "haw", "hi", "hu", "hy", "ia", "io", "is", "ji", "jgo", "jmc", "ka",
+            "kab", "kaj", "kcg", "kk", "kkj", "kl", "kn", "ks", "ksb", "ku", "ky", "lb", "lg",
+            "ln", "mas", "mg", "mgo", "mk", "ml", "mn", "mr", "nah", "nb", "nd", "ne", "nl", "nn",
+            "nnh", "no", "nr", "nso", "ny", "nyn", "om", "or", "os", "pa", "pap", "pcm", "ps",
+            "rm", "rof", "rwk", "saq", "sc", "scn", "sd", "sdh", "seh", "si", "sn", "so", "sq",
+            "ss", "ssy", "st", "sv", "sw", "syr", "ta", "te", "teo", "ti", "tig", "tk", "tl", "tn",
+            "tr", "ts", "tzm", "ug", "ur", "uz", "ve", "vo", "vun", "wa", "wae", "xh", "xog", "yi",
+            "zu",
+        ],
+        ONE_OTHER,
+    ),
+    (&["ksh", "lag", "lv", "prg"], ZERO_ONE_OTHER),
+    (
+        &[
+            "he", "iu", "naq", "sat", "se", "sma", "smi", "smj", "smn", "sms",
+        ],
+        ONE_TWO_OTHER,
+    ),
+    (&["bs", "hr", "mo", "ro", "sh", "shi", "sr"], ONE_FEW_OTHER),
+    (&["ca", "es", "fr", "it", "pt", "vec"], ONE_MANY_OTHER),
+    (&["dsb", "gd", "hsb", "sl"], ONE_TWO_FEW_OTHER),
+    (
+        &["be", "cs", "lt", "pl", "ru", "sk", "uk"],
+        ONE_FEW_MANY_OTHER,
+    ),
+    (&["br", "ga", "gv", "mt"], ONE_TWO_FEW_MANY_OTHER),
+    (&["ar", "ars", "cy", "kw"], ALL),
+];
+
+/// The plural categories a language needs, in CLDR order. None for languages without bundled rules.
+/// Regions and scripts are ignored, e.g.: pt-BR and zh-Hans
+pub fn plural_categories(language_code: &str) -> Option<&'static [PluralVariate]> {
+    let language = primary_subtag(language_code);
+
+    RULES
+        .iter()
+        .find(|(languages, _)| languages.contains(&language.as_str()))
+        .map(|(_, categories)| *categories)
+}
+
+/// The gettext plural forms of a language: the expression picks n of msgstr[n], the variates are the categories per n.
+/// Gettext only counts integers, so categories only used by fractions don't have an n, e.g.: other for Polish.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct GettextPluralForms {
+    pub expression: &'static str,
+    pub variates: &'static [PluralVariate],
+}
+
+impl GettextPluralForms {
+    /// The value of the Plural-Forms header, e.g.: nplurals=2; plural=(n != 1);
+    pub fn header(&self) -> String {
+        format!(
+            "nplurals={}; plural={};",
+            self.variates.len(),
+            self.expression
+        )
+    }
+}
+
+/// The gettext expressions of the languages that don't use the default of their categories, derived from [RULES] for integers
+const GETTEXT_RULES: &[(&[&str], &str, &[PluralVariate])] = &[
+    (
+        &[
+            "ak", "am", "as", "bho", "bn", "fa", "ff", "gu", "guw", "hi", "hy", "kab", "kn", "ln",
+            "mg", "nso", "pa", "pcm", "si", "ti", "wa", "zu",
+        ],
+        "(n > 1)",
+        ONE_OTHER,
+    ),
+    (&["is", "mk"], "(n%10 != 1 || n%100 == 11)", ONE_OTHER),
+    (
+        &["ceb", "fil", "tl"],
+        "(n%10 == 4 || n%10 == 6 || n%10 == 9)",
+        ONE_OTHER,
+    ),
+    (&["tzm"], "(n > 1 && (n < 11 || n > 99))", ONE_OTHER),
+    (
+        &["lv", "prg"],
+        "(n%10 == 0 || (n%100 >= 11 && n%100 <= 19) ? 0 : n%10 == 1 && n%100 != 11 ? 1 : 2)",
+        ZERO_ONE_OTHER,
+    ),
+    (
+        &["ksh", "lag"],
+        "(n == 0 ? 0 : n == 1 ? 1 : 2)",
+        ZERO_ONE_OTHER,
+    ),
+    (
+        &[
+            "he", "iu", "naq", "sat", "se", "sma", "smi", "smj", "smn", "sms",
+        ],
+        "(n == 1 ? 0 : n == 2 ? 1 : 2)",
+        ONE_TWO_OTHER,
+    ),
+    (
+        &["bs", "hr", "sh", "sr"],
+        "(n%10 == 1 && n%100 != 11 ? 0 : n%10 >= 2 && n%10 <= 4 && (n%100 < 12 || n%100 > 14) ? 1 : 2)",
+        ONE_FEW_OTHER,
+    ),
+    (
+        &["be", "ru", "uk"],
+        "(n%10 == 1 && n%100 != 11 ? 0 : n%10 >= 2 && n%10 <= 4 && (n%100 < 12 || n%100 > 14) ? 1 : 2)",
+        &[One, Few, Many],
+    ),
+    (
+        &["mo", "ro"],
+        "(n == 1 ? 0 : n == 0 || (n%100 >= 2 && n%100 <= 19) ? 1 : 2)",
+        ONE_FEW_OTHER,
+    ),
+    (&["shi"], "(n <= 1 ? 0 : n <= 10 ? 1 : 2)", ONE_FEW_OTHER),
+    (
+        &["fr", "pt"],
+        "(n == 0 || n == 1 ? 0 : n%1000000 == 0 ? 1 : 2)",
+        ONE_MANY_OTHER,
+    ),
+    (
+        &["ca", "es", "it", "vec"],
+        "(n == 1 ? 0 : n != 0 && n%1000000 == 0 ? 1 : 2)",
+        ONE_MANY_OTHER,
+    ),
+    (
+        &["dsb", "hsb", "sl"],
+        "(n%100 == 1 ? 0 : n%100 == 2 ? 1 : n%100 == 3 || n%100 == 4 ? 2 : 3)",
+        ONE_TWO_FEW_OTHER,
+    ),
+    (
+        &["gd"],
+        "(n == 1 || n == 11 ? 0 : n == 2 || n == 12 ? 1 : (n >= 3 && n <= 10) || (n >= 13 && n <= 19) ? 2 : 3)",
+        ONE_TWO_FEW_OTHER,
+    ),
+    (
+        &["pl"],
+        "(n == 1 ? 0 : n%10 >= 2 && n%10 <= 4 && (n%100 < 12 || n%100 > 14) ? 1 : 2)",
+        &[One, Few, Many],
+    ),
+    (
+        &["cs", "sk"],
+        "(n == 1 ? 0 : n >= 2 && n <= 4 ? 1 : 2)",
+        ONE_FEW_OTHER,
+    ),
+    (
+        &["lt"],
+        "(n%10 == 1 && (n%100 < 11 || n%100 > 19) ? 0 : n%10 >= 2 && (n%100 < 11 || n%100 > 19) ? 1 : 2)",
+        ONE_FEW_OTHER,
+    ),
+    (
+        &["ga"],
+        "(n == 1 ? 0 : n == 2 ? 1 : n >= 3 && n <= 6 ? 2 : n >= 7 && n <= 10 ? 3 : 4)",
+        ONE_TWO_FEW_MANY_OTHER,
+    ),
+    (
+        &["gv"],
+        "(n%10 == 1 ? 0 : n%10 == 2 ? 1 : n%20 == 0 ? 2 : 3)",
+        ONE_TWO_FEW_OTHER,
+    ),
+    (
+        &["mt"],
+        "(n == 1 ? 0 : n == 2 ? 1 : n == 0 || (n%100 >= 3 && n%100 <= 10) ? 2 : n%100 >= 11 && n%100 <= 19 ? 3 : 4)",
+        ONE_TWO_FEW_MANY_OTHER,
+    ),
+    (
+        &["ar", "ars"],
+        "(n == 0 ? 0 : n == 1 ? 1 : n == 2 ? 2 : n%100 >= 3 && n%100 <= 10 ? 3 : n%100 >= 11 ? 4 : 5)",
+        ALL,
+    ),
+    (
+        &["cy"],
+        "(n == 0 ? 0 : n == 1 ? 1 : n == 2 ? 2 : n == 3 ? 3 : n == 6 ? 4 : 5)",
+        ALL,
+    ),
+];
+
+/// The gettext plural forms of a language, None for languages without bundled rules, e.g.: br
+pub fn gettext_plural_forms(language_code: &str) -> Option<GettextPluralForms> {
+    let language = primary_subtag(language_code);
+
+    if let Some((_, expression, variates)) = GETTEXT_RULES
+        .iter()
+        .find(|(languages, _, _)| languages.contains(&language.as_str()))
+    {
+        return Some(GettextPluralForms {
+            expression,
+            variates,
+        });
+    }
+
+    match plural_categories(language_code)? {
+        OTHER => Some(GettextPluralForms {
+            expression: "0",
+            variates: OTHER,
+        }),
+        ONE_OTHER => Some(GettextPluralForms {
+            expression: "(n != 1)",
+            variates: ONE_OTHER,
+        }),
+        _ => None,
+    }
+}
+
+fn primary_subtag(language_code: &str) -> String {
+    language_code
+        .split(['-', '_'])
+        .next()
+        .unwrap_or_default()
+        .to_lowercase()
+}
+
+#[cfg(test)]
+mod test {
+    use super::{gettext_plural_forms, plural_categories, GETTEXT_RULES};
+    use swift_localizable_json_parser::types::output::PluralVariate;
+
+    #[test]
+    fn test_plural_categories() {
+        assert_eq!(
+            Some(
+                [
+                    PluralVariate::One,
+                    PluralVariate::Few,
+                    PluralVariate::Many,
+                    PluralVariate::Other
+                ]
+                .as_slice()
+            ),
+            plural_categories("pl")
+        );
+        assert_eq!(
+            Some([PluralVariate::Other].as_slice()),
+            plural_categories("zh-Hans")
+        );
+        assert_eq!(plural_categories("pt"), plural_categories("pt_BR"));
+        assert_eq!(6, plural_categories("ar").unwrap().len());
+        assert_eq!(None, plural_categories("xx"));
+    }
+
+    #[test]
+    fn test_gettext_plural_forms() {
+        let pl = gettext_plural_forms("pl").unwrap();
+
+        assert_eq!(
+            [PluralVariate::One, PluralVariate::Few, PluralVariate::Many].as_slice(),
+            pl.variates
+        );
+        assert!(pl.header().starts_with("nplurals=3; plural=(n == 1 ? 0"));
+        assert_eq!(
+            "nplurals=2; plural=(n != 1);",
+            gettext_plural_forms("nl").unwrap().header()
+        );
+        assert_eq!(
+            "nplurals=1; plural=0;",
+            gettext_plural_forms("zh-Hans").unwrap().header()
+        );
+        assert_eq!(6, gettext_plural_forms("ar").unwrap().variates.len());
+        assert_eq!(None, gettext_plural_forms("br"));
+
+        for (languages, _, variates) in GETTEXT_RULES {
+            for language in *languages {
+                let categories = plural_categories(language).unwrap();
+
+                assert!(
+                    variates.iter().all(|v| categories.contains(v)),
+                    "{language}"
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,16 @@
+use clap::Parser;
+
+use std::path::PathBuf;
+
+#[derive(Clone, Debug, Parser)]
+pub struct Config {
+    #[clap(long)]
+    pub save_in: PathBuf,
+    #[clap(long)]
+    pub clean_dir_before_generating: bool,
+    /// If you added a new language, add it to the list so a new po file can be generated
+    #[clap(long)]
+    pub new_language_codes: Vec<String>,
+    #[clap(long)]
+    pub path_to_xcstrings: PathBuf,
+}
@@ -0,0 +1,291 @@
+use crate::docx_writer::convert::Export;
+use crate::error::ConvertError;
+use crate::plural_rules::gettext_plural_forms;
+use crate::po_writer::config::Config;
+use crate::rows::{languages_to_write, rows_for_language, Row};
+use swift_localizable_json_parser::types::output::Parsed;
+use swift_localizable_json_parser::TRANSLATED_STATE;
+
+/// Used as the template name if the catalog isn't read from a file
+const DEFAULT_PROJECT: &str = "Localizable";
+/// Translator comment which lists the plural variates, in the same order as the msgstr[n] lines.
+/// Only written for languages without gettext plural forms, see [gettext_plural_forms]
+pub const VARIATIONS_COMMENT: &str = "Variations:";
+pub const PLURAL_FORMS_HEADER: &str = "Plural-Forms:";
+pub const FUZZY_FLAG: &str = "fuzzy";
+
+pub fn convert_from_path(config: Config) -> Result<Vec<Export>, ConvertError> {
+    ConvertError::ensure_exists(&config.path_to_xcstrings)?;
+
+    let read = std::fs::read(&config.path_to_xcstrings)
+        .map_err(ConvertError::io(&config.path_to_xcstrings))?;
+
+    convert_from_raw(&read, config)
+}
+
+pub fn convert_from_raw(xcstrings: &[u8], config: Config) -> Result<Vec<Export>, ConvertError> {
+    convert(
+        swift_localizable_json_parser::parse_from_bytes(xcstrings)?,
+        config,
+    )
+}
+
+/// Writes a .pot template of the base language and a .po file per language to translate to.
+/// The template is the first export, its language code is the base language.
+pub fn convert(localizable: Parsed, config: Config) -> Result<Vec<Export>, ConvertError> {
+    let localized_per_language = localizable.localizable.localized_per_language();
+
+    if config.clean_dir_before_generating {
+        // Dir does not have to exist
+        let _ = std::fs::remove_dir_all(&config.save_in);
+    }
+
+    // Ignore any error
+    let _ = std::fs::create_dir_all(&config.save_in);
+
+    let base_language = &localizable.translation.source_language;
+    let project = config
+        .path_to_xcstrings
+        .file_stem()
+        .map(|f| f.to_string_lossy().to_string())
+        .unwrap_or(DEFAULT_PROJECT.to_string());
+    let mut exports = vec![];
+
+    let template_rows = rows_for_language(&localized_per_language, base_language, base_language);
+    let template_file_name = format!("{}.pot", project);
+
+    let template_path = config.save_in.join(&template_file_name);
+
+    std::fs::write(
+        &template_path,
+        to_po(base_language, None, &project, &template_rows),
+    )
+    .map_err(ConvertError::io(&template_path))?;
+
+    exports.push(Export {
+        amount_keys_to_translate: template_rows.len() as i32,
+        amount_keys_total: template_rows.len() as i32,
+        language_code: base_language.to_string(),
+        file_name: template_file_name,
+    });
+
+    for language_to_write in languages_to_write(
+        &localized_per_language,
+        base_language,
+        config.new_language_codes,
+    ) {
+        log::debug!("Writing po for language: {language_to_write}");
+
+        let rows = rows_for_language(&localized_per_language, base_language, &language_to_write);
+        let po = to_po(base_language, Some(&language_to_write), &project, &rows);
+        let file_name = format!("{}.po", language_to_write);
+
+        let path = config.save_in.join(&file_name);
+
+        std::fs::write(&path, po).map_err(ConvertError::io(&path))?;
+
+        exports.push(Export {
+            amount_keys_to_translate: rows.len() as i32,
+            amount_keys_total: rows.len() as i32,
+            language_code: language_to_write,
+            file_name,
+        })
+    }
+
+    Ok(exports)
+}
+
+/// Creates a po file, or a pot template if there is no language.
+/// The key is the msgctxt, so identical base values of different keys never collide.
+/// The msgstr[n] of plurals follow the Plural-Forms of the language, so PO editors show them as the right form.
+pub fn to_po(base_language: &str, language: Option<&str>, project: &str, rows: &[Row]) -> String {
+    let plural_forms = language.and_then(gettext_plural_forms);
+    let mut po = vec![
+        "msgid \"\"".to_string(),
+        "msgstr \"\"".to_string(),
+        quote(&format!("Project-Id-Version: {project}\n")),
+        quote(&format!("Language: {}\n", language.unwrap_or_default())),
+        quote("MIME-Version: 1.0\n"),
+        quote("Content-Type: text/plain; charset=UTF-8\n"),
+        quote("Content-Transfer-Encoding: 8bit\n"),
+        quote(&format!("X-Source-Language: {base_language}\n")),
+    ];
+
+    if let Some(plural_forms) = &plural_forms {
+        po.push(quote(&format!(
+            "{PLURAL_FORMS_HEADER} {}\n",
+            plural_forms.header()
+        )));
+    }
+
+    po.push(String::new());
+
+    let mut index = 0;
+
+    while index < rows.len() {
+        let row = &rows[index];
+        // Plural variates of the same key are next to each other
+        let group = if row.variation.is_some() {
+            rows[index..]
+                .iter()
+                .take_while(|r| r.key == row.key && r.variation.is_some())
+                .count()
+        } else {
+            1
+        };
+        let grouped = &rows[index..index + group];
+
+        index += group;
+
+        for line in row.comment.lines() {
+            po.push(format!("#. {line}"));
+        }
+
+        if row.variation.is_some() && plural_forms.is_none() {
+            let variations = grouped
+                .iter()
+                .map(|r| r.variation_text().to_lowercase())
+                .collect::<Vec<_>>();
+
+            po.push(format!("# {VARIATIONS_COMMENT} {}", variations.join(" ")));
+        }
+
+        // An empty msgstr is already untranslated, only the values which need work are marked as fuzzy
+        let is_fuzzy = language.is_some()
+            && grouped
+                .iter()
+                .any(|r| r.state != TRANSLATED_STATE && !r.translated_value.is_empty());
+
+        if is_fuzzy {
+            po.push(format!("#, {FUZZY_FLAG}"));
+        }
+
+        po.push(format!("msgctxt {}", quote(&row.key)));
+
+        let translated_value = |row: &Row| {
+            if language.is_some() {
+                row.translated_value.clone()
+            } else {
+                String::new()
+            }
+        };
+
+        if row.variation.is_some() {
+            po.push(format!("msgid {}", quote(&grouped[0].base_value)));
+            po.push(format!(
+                "msgid_plural {}",
+                quote(&grouped[grouped.len() - 1].base_value)
+            ));
+
+            let msgstrs = match &plural_forms {
+                None => grouped.iter().map(translated_value).collect::<Vec<_>>(),
+                // Categories only used by fractions have no msgstr[n]
+                Some(plural_forms) => plural_forms
+                    .variates
+                    .iter()
+                    .map(|variate| {
+                        grouped
+                            .iter()
+                            .find(|r| r.variation.as_ref() == Some(variate))
+                            .map(translated_value)
+                            .unwrap_or_default()
+                    })
+                    .collect(),
+            };
+
+            for (position, msgstr) in msgstrs.iter().enumerate() {
+                po.push(format!("msgstr[{position}] {}", quote(msgstr)));
+            }
+        } else {
+            po.push(format!("msgid {}", quote(&row.base_value)));
+            po.push(format!("msgstr {}", quote(&translated_value(row))));
+        }
+
+        po.push(String::new());
+    }
+
+    po.join("\n")
+}
+
+/// Quotes a value, values with newlines are split over multiple lines like gettext does
+fn quote(value: &str) -> String {
+    let escape = |value: &str| {
+        format!(
+            "\"{}\"",
+            value
+                .replace('\\', "\\\\")
+                .replace('"', "\\\"")
+                .replace('\n', "\\n")
+                .replace('\t', "\\t")
+                .replace('\r', "\\r")
+        )
+    };
+    let lines = value.split_inclusive('\n').collect::<Vec<_>>();
+
+    if lines.len() <= 1 {
+        return escape(value);
+    }
+
+    let mut quoted = vec!["\"\"".to_string()];
+
+    quoted.extend(lines.into_iter().map(escape));
+
+    quoted.join("\n")
+}
+
+#[cfg(test)]
+mod test {
+    use crate::po_writer::config::Config;
+    use std::env::current_dir;
+    use swift_localizable_json_parser::types::output::PluralVariate;
+
+    #[test]
+    fn test_convert() {
+        let base = current_dir().unwrap();
+        let save_in = base.join("generated").join("po");
+        let exports = super::convert_from_path(Config {
+            save_in: save_in.clone(),
+            clean_dir_before_generating: true,
+            new_language_codes: vec!["pl".to_string()],
+            path_to_xcstrings: base.join("resources").join("reader_test_base.xcstrings"),
+        })
+        .unwrap();
+
+        assert_eq!(3, exports.len());
+        assert_eq!("reader_test_base.pot", exports[0].file_name);
+
+        let template = std::fs::read_to_string(save_in.join("reader_test_base.pot")).unwrap();
+
+        assert!(!template.contains("#, fuzzy"));
+
+        let extracted =
+            crate::xcstrings_po_merger::po_extractor::extract(&save_in.join("nl.po")).unwrap();
+        let newlines = extracted
+            .extracted
+            .iter()
+            .find(|e| e.key == "Inlined\nwith \n\n newlines")
+            .unwrap();
+
+        assert_eq!("nl", extracted.language_code);
+        assert_eq!("Ingelijnd\nmet \n\n newlines", newlines.translated);
+
+        // The msgstr[n] of Polish follow its Plural-Forms, other is only used by fractions
+        let pl = std::fs::read_to_string(save_in.join("pl.po"))
+            .unwrap()
+            .replace("msgstr[1] \"\"", "msgstr[1] \"%1$lld kilka\"");
+
+        assert!(pl.contains("Plural-Forms: nplurals=3;"));
+        assert!(!pl.contains("msgstr[3]"));
+
+        let extracted = crate::xcstrings_po_merger::po_extractor::extract_from_raw(&pl).unwrap();
+        let plurals = extracted
+            .extracted
+            .iter()
+            .filter(|e| e.key == "plurals")
+            .collect::<Vec<_>>();
+
+        assert_eq!(3, plurals.len());
+        assert_eq!(Some(PluralVariate::Few), plurals[1].variation);
+        assert_eq!("%1$lld kilka", plurals[1].translated);
+    }
+}
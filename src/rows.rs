@@ -0,0 +1,283 @@
+use crate::devices::{device_unit_id, device_variation_text, DeviceVariate};
+use crate::docx_writer::config::Column;
+use crate::error::ConvertError;
+use crate::plural_rules::plural_categories;
+use crate::xliff_writer::convert::unit_id;
+use crate::{KEY_COMMENT, KEY_KEY, KEY_STATE, KEY_VARIATION};
+use std::collections::HashSet;
+use swift_localizable_json_parser::types::inoutoutput::TranslationValue;
+use swift_localizable_json_parser::types::output::{
+    LocalizedPerLanguage, PluralVariate, Translation,
+};
+use swift_localizable_json_parser::NEW_STATE;
+
+/// A single line to translate: a key, or one plural variate of a key.
+/// Every writer (docx, xliff, ...) emits the same rows, only the layout differs.
+#[derive(Clone, Debug)]
+pub struct Row {
+    pub key: String,
+    pub comment: String,
+    pub variation: Option<PluralVariate>,
+    /// Only set for keys with device variations, see [crate::devices::DeviceVariations]
+    pub device: Option<DeviceVariate>,
+    pub state: String,
+    pub base_value: String,
+    pub translated_value: String,
+}
+
+impl Row {
+    /// The value of the variation column, N/A if it isn't a plural or device variation
+    pub fn variation_text(&self) -> String {
+        match self.device {
+            Some(device) => device_variation_text(device, self.variation.as_ref()),
+            None => self
+                .variation
+                .as_ref()
+                .map(|v| v.android_key())
+                .unwrap_or("N/A")
+                .to_string(),
+        }
+    }
+
+    /// The unit id of [unit_id], or of [device_unit_id] for device rows
+    pub fn unit_id(&self) -> String {
+        match self.device {
+            None => unit_id(&self.key, self.variation.as_ref()),
+            Some(device) => device_unit_id(&self.key, device, self.variation.as_ref()),
+        }
+    }
+
+    /// The cells of table like formats, in the same order as [header]
+    pub fn cells(&self, columns_in_output: &[Column]) -> Vec<String> {
+        let mut cells = vec![
+            self.key.clone(),
+            self.comment.clone(),
+            self.variation_text(),
+        ];
+
+        if columns_in_output.contains(&Column::State) {
+            cells.push(self.state.clone());
+        }
+
+        cells.push(self.base_value.clone());
+        cells.push(self.translated_value.clone());
+
+        cells
+    }
+}
+
+/// The header of table like formats, the language to translate to is always the last column
+pub fn header(base_language: &str, language: &str, columns_in_output: &[Column]) -> Vec<String> {
+    let mut header = vec![
+        KEY_KEY.to_string(),
+        KEY_COMMENT.to_string(),
+        KEY_VARIATION.to_string(),
+    ];
+
+    if columns_in_output.contains(&Column::State) {
+        header.push(KEY_STATE.to_string());
+    }
+
+    header.push(base_language.to_string());
+    header.push(language.to_string());
+
+    header
+}
+
+/// The header of a column that a document with multiple languages has per language, e.g.: State (nl)
+pub fn per_language_header(header: &str, language: &str) -> String {
+    format!("{header} ({language})")
+}
+
+/// The plural variates in the order of CLDR
+pub const PLURAL_VARIATES: [PluralVariate; 6] = [
+    PluralVariate::Zero,
+    PluralVariate::One,
+    PluralVariate::Two,
+    PluralVariate::Few,
+    PluralVariate::Many,
+    PluralVariate::Other,
+];
+
+/// Case insensitive version of [PluralVariate::from_android_key], the android key of zero is capitalized
+pub fn variate_from_key(key: &str) -> Option<PluralVariate> {
+    PluralVariate::from_android_key(key).or_else(|| {
+        PLURAL_VARIATES
+            .into_iter()
+            .find(|variate| variate.android_key().eq_ignore_ascii_case(key))
+    })
+}
+
+/// All languages in the catalog plus the new languages, without the base language
+pub fn languages_to_write(
+    localized_per_language: &LocalizedPerLanguage,
+    base_language: &str,
+    new_language_codes: Vec<String>,
+) -> HashSet<String> {
+    let mut languages_to_write = localized_per_language
+        .language_localized
+        .keys()
+        .map(|k| k.to_string())
+        .collect::<HashSet<_>>();
+
+    languages_to_write.extend(new_language_codes);
+
+    assert!(languages_to_write.remove(base_language));
+
+    languages_to_write
+}
+
+/// Splits the languages over the documents to write, the languages of a group (comma separated, e.g. nl,de) share a document.
+/// Every other language gets a document of its own.
+pub fn group_languages(
+    language_groups: &[String],
+    mut languages_to_write: HashSet<String>,
+) -> Result<Vec<Vec<String>>, ConvertError> {
+    let mut groups = vec![];
+
+    for language_group in language_groups {
+        let group = language_group
+            .split(',')
+            .map(|language| language.trim().to_string())
+            .filter(|language| !language.is_empty())
+            .collect::<Vec<_>>();
+
+        for language in &group {
+            if !languages_to_write.remove(language) {
+                return Err(ConvertError::from(format!(
+                    "Language {language} of group {language_group} is not a language to write or is in another group"
+                )));
+            }
+        }
+
+        if !group.is_empty() {
+            groups.push(group);
+        }
+    }
+
+    let mut ungrouped = languages_to_write.into_iter().collect::<Vec<_>>();
+
+    ungrouped.sort();
+    groups.extend(ungrouped.into_iter().map(|language| vec![language]));
+
+    Ok(groups)
+}
+
+/// The rows of every key of the base language. Plurals get exactly the categories the language needs according to the CLDR rules,
+/// languages without bundled rules get the variates of the base language plus the variates they already have.
+pub fn rows_for_language(
+    localized_per_language: &LocalizedPerLanguage,
+    base_language: &str,
+    language_to_write: &str,
+) -> Vec<Row> {
+    let base_translation = &localized_per_language
+        .language_localized
+        .get(base_language)
+        .unwrap()
+        .translations;
+    let localization_language_to = localized_per_language
+        .language_localized
+        .get(language_to_write)
+        .cloned()
+        .unwrap_or_default();
+    let mut rows = vec![];
+
+    macro_rules! push_row {
+        ($localized: expr, $variation: expr, $translation_value: expr, $base_translation_value: expr) => {{
+            let translation_value: Option<TranslationValue> = $translation_value;
+
+            rows.push(Row {
+                key: $localized.key_raw.clone(),
+                comment: $localized.comment.clone(),
+                variation: $variation,
+                device: None,
+                state: translation_value
+                    .clone()
+                    .map(|t| t.state)
+                    .unwrap_or(NEW_STATE.to_string()),
+                base_value: $base_translation_value.to_string(),
+                translated_value: translation_value.map(|t| t.value).unwrap_or("".to_string()),
+            });
+        }};
+    }
+
+    for localized_base_translation in base_translation {
+        let existing_translation = localization_language_to
+            .translations
+            .iter()
+            .find(|t| t.key_raw == localized_base_translation.key_raw)
+            .map(|t| t.translation.clone());
+
+        match &localized_base_translation.translation {
+            Translation::Localization(base) => {
+                let existing = existing_translation.map(|e| e.expect_localization());
+
+                push_row!(localized_base_translation, None, existing, base.value)
+            }
+            Translation::PluralVariation(base) => {
+                let existing = existing_translation
+                    .map(|e| e.expect_plural_variation())
+                    .unwrap_or_default();
+                let existing_value = |variate: &PluralVariate| {
+                    existing
+                        .iter()
+                        .find(|e| &e.variate == variate)
+                        .map(|e| e.translation_value.clone())
+                };
+                let categories = plural_categories(language_to_write)
+                    .filter(|_| language_to_write != base_language);
+
+                match categories {
+                    Some(categories) => {
+                        // A category the base language doesn't have, e.g. few for Polish, is translated from the other value
+                        for variate in categories {
+                            let base_value = base
+                                .iter()
+                                .find(|pv| &pv.variate == variate)
+                                .or_else(|| {
+                                    base.iter().find(|pv| pv.variate == PluralVariate::Other)
+                                })
+                                .map(|pv| pv.translation_value.value.as_str())
+                                .unwrap_or_default();
+
+                            push_row!(
+                                localized_base_translation,
+                                Some(variate.clone()),
+                                existing_value(variate),
+                                base_value
+                            )
+                        }
+                    }
+                    None => {
+                        // First handle the default plural variations from the base language
+                        for pv in base {
+                            push_row!(
+                                localized_base_translation,
+                                Some(pv.variate.clone()),
+                                existing_value(&pv.variate),
+                                pv.translation_value.value
+                            )
+                        }
+
+                        // It could also be that the language already has other plural variations than the base language, check that
+                        for pv in &existing {
+                            if base.iter().any(|p| p.variate == pv.variate) {
+                                // Already a variation for it
+                                continue;
+                            }
+
+                            push_row!(
+                                localized_base_translation,
+                                Some(pv.variate.clone()),
+                                Some(pv.translation_value.clone()),
+                                ""
+                            );
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    rows
+}
@@ -0,0 +1,12 @@
+use clap::Parser;
+use std::path::PathBuf;
+
+#[derive(Clone, Debug, Parser)]
+pub struct Config {
+    /// The path to an existing .xcstrings file to export the translated pairs from
+    #[clap(long)]
+    pub path_to_xcstrings: PathBuf,
+    /// The path of the .tmx file to write
+    #[clap(long)]
+    pub write_to: PathBuf,
+}
@@ -0,0 +1,170 @@
+use crate::error::ConvertError;
+use crate::rows::{languages_to_write, rows_for_language};
+use crate::tmx_writer::config::Config;
+use crate::translation_memory::is_reusable;
+use crate::xliff_writer::convert::unit_id;
+use crate::xml::escape;
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+const CREATION_TOOL: &str = "xcstringsdocx";
+
+#[derive(Clone, Debug, Serialize)]
+pub struct Export {
+    pub amount_translation_units: i32,
+    pub languages: Vec<String>,
+}
+
+/// A source segment with the translations of every language
+struct TranslationUnit {
+    comment: String,
+    base_value: String,
+    translations: Vec<(String, String)>,
+}
+
+pub fn write_tmx(config: Config) -> Result<Export, ConvertError> {
+    ConvertError::ensure_exists(&config.path_to_xcstrings)?;
+
+    let parsed = swift_localizable_json_parser::parse_from_file(&config.path_to_xcstrings)?;
+    let localized_per_language = parsed.localizable.localized_per_language();
+    let base_language = &parsed.translation.source_language;
+    let mut languages = languages_to_write(&localized_per_language, base_language, vec![])
+        .into_iter()
+        .collect::<Vec<_>>();
+
+    languages.sort();
+
+    let mut translation_units: BTreeMap<String, TranslationUnit> = BTreeMap::new();
+
+    for language in &languages {
+        for row in rows_for_language(&localized_per_language, base_language, language) {
+            if !is_reusable(&row) {
+                continue;
+            }
+
+            translation_units
+                .entry(unit_id(&row.key, row.variation.as_ref()))
+                .or_insert_with(|| TranslationUnit {
+                    comment: row.comment.clone(),
+                    base_value: row.base_value.clone(),
+                    translations: vec![],
+                })
+                .translations
+                .push((language.clone(), row.translated_value));
+        }
+    }
+
+    let mut xml = vec![
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>".to_string(),
+        "<tmx version=\"1.4\">".to_string(),
+        format!(
+            "  <header creationtool=\"{CREATION_TOOL}\" creationtoolversion=\"{}\" segtype=\"sentence\" o-tmf=\"xcstrings\" adminlang=\"{}\" srclang=\"{}\" datatype=\"plaintext\"/>",
+            env!("CARGO_PKG_VERSION"),
+            escape(base_language),
+            escape(base_language)
+        ),
+        "  <body>".to_string(),
+    ];
+
+    for (tuid, translation_unit) in &translation_units {
+        xml.push(format!("    <tu tuid=\"{}\">", escape(tuid)));
+
+        if !translation_unit.comment.is_empty() {
+            xml.push(format!(
+                "      <note>{}</note>",
+                escape(&translation_unit.comment)
+            ));
+        }
+
+        xml.push(tuv(base_language, &translation_unit.base_value));
+
+        for (language, translated) in &translation_unit.translations {
+            xml.push(tuv(language, translated));
+        }
+
+        xml.push("    </tu>".to_string());
+    }
+
+    xml.push("  </body>".to_string());
+    xml.push("</tmx>".to_string());
+
+    if let Some(parent) = config.write_to.parent() {
+        std::fs::create_dir_all(parent).map_err(ConvertError::io(parent))?;
+    }
+
+    std::fs::write(&config.write_to, xml.join("\n")).map_err(ConvertError::io(&config.write_to))?;
+
+    Ok(Export {
+        amount_translation_units: translation_units.len() as i32,
+        languages,
+    })
+}
+
+fn tuv(language: &str, value: &str) -> String {
+    format!(
+        "      <tuv xml:lang=\"{}\"><seg>{}</seg></tuv>",
+        escape(language),
+        escape(value)
+    )
+}
+
+#[cfg(test)]
+mod test {
+    use crate::tmx_writer::config::Config;
+    use crate::translation_memory::TranslationMemory;
+    use std::env::current_dir;
+
+    #[test]
+    fn test_write_tmx() {
+        let base = current_dir().unwrap();
+        let write_to = base.join("generated").join("tmx").join("memory.tmx");
+        let export = super::write_tmx(Config {
+            path_to_xcstrings: base.join("resources").join("reader_test_base.xcstrings"),
+            write_to: write_to.clone(),
+        })
+        .unwrap();
+
+        assert_eq!(vec!["nl".to_string()], export.languages);
+
+        let memory = TranslationMemory::read(std::slice::from_ref(&write_to)).unwrap();
+
+        assert_eq!(
+            Some("Ingelijnd\nmet \n\n newlines"),
+            memory.exact_match("en", "Inlined\nwith \n\n newlines", "nl")
+        );
+        assert_eq!(None, memory.exact_match("en", "normal", "pl"));
+
+        // normal_1 has the same base value as the translated normal_0
+        let save_in = base.join("generated").join("tmx").join("docx");
+
+        crate::docx_writer::convert::convert_from_path(crate::docx_writer::config::Config {
+            save_in: save_in.clone(),
+            clean_dir_before_generating: true,
+            new_language_codes: vec![],
+            path_to_xcstrings: base.join("resources").join("reader_test_base.xcstrings"),
+            columns_in_output: vec![
+                crate::docx_writer::config::Column::State,
+                crate::docx_writer::config::Column::Suggestion,
+            ],
+            translation_memories: vec![write_to],
+            suggestion_catalogs: vec![],
+            suggestion_threshold: 70,
+            language_groups: vec![],
+            filter: Default::default(),
+        })
+        .unwrap();
+
+        let extracted =
+            crate::xcstrings_docx_merger::docx_extractor::extract(&save_in.join("nl.docx"))
+                .unwrap()
+                .remove(0);
+        let normal_1 = extracted
+            .extracted
+            .iter()
+            .find(|e| e.key == "normal_1")
+            .unwrap();
+
+        assert_eq!("nl", extracted.language_code);
+        assert_eq!("normaal", normal_1.translated);
+    }
+}
@@ -0,0 +1,268 @@
+use crate::devices::rows_with_devices;
+use crate::error::ConvertError;
+use crate::rows::{languages_to_write, Row};
+use crate::xcstrings_docx_merger::convert::read_xcstrings_with_devices;
+use crate::xml::text_of;
+use crate::PREFILLED_STATE;
+use std::collections::HashMap;
+use std::path::PathBuf;
+use swift_localizable_json_parser::TRANSLATED_STATE;
+
+const XML_NAMESPACE: &str = "http://www.w3.org/XML/1998/namespace";
+
+/// The segments of TMX files or other catalogs, looked up by exact or similar source text
+#[derive(Clone, Debug, Default)]
+pub struct TranslationMemory {
+    /// (source language, source text, target language) -> target text, languages are lowercased
+    matches: HashMap<(String, String, String), String>,
+}
+
+impl TranslationMemory {
+    /// Reads the TMX files in order, the first file with a match wins
+    pub fn read(tmx_files: &[PathBuf]) -> Result<Self, ConvertError> {
+        let mut translation_memory = Self::default();
+
+        for tmx_file in tmx_files {
+            if !tmx_file.exists() {
+                return Err(ConvertError::MissingFile {
+                    path: tmx_file.clone(),
+                });
+            }
+
+            translation_memory.add_from_raw(
+                &std::fs::read_to_string(tmx_file).map_err(ConvertError::io(tmx_file))?,
+            )?;
+        }
+
+        Ok(translation_memory)
+    }
+
+    /// Adds the translated pairs of another catalog
+    pub fn add_from_xcstrings(&mut self, xcstrings: &PathBuf) -> Result<(), ConvertError> {
+        let (parsed, devices) = read_xcstrings_with_devices(xcstrings)?;
+        let localized_per_language = parsed.localizable.localized_per_language();
+        let base_language = &parsed.translation.source_language;
+        let languages =
+            languages_to_write(&localized_per_language, base_language, devices.languages());
+
+        for language in languages {
+            for row in
+                rows_with_devices(&localized_per_language, base_language, &devices, &language)
+            {
+                if is_reusable(&row) {
+                    self.insert(
+                        base_language,
+                        row.base_value,
+                        &language,
+                        row.translated_value,
+                    );
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    fn insert(
+        &mut self,
+        source_language: &str,
+        source: String,
+        target_language: &str,
+        target: String,
+    ) {
+        self.matches
+            .entry((
+                source_language.to_lowercase(),
+                source,
+                target_language.to_lowercase(),
+            ))
+            .or_insert(target);
+    }
+
+    pub fn add_from_raw(&mut self, raw: &str) -> Result<(), ConvertError> {
+        let document = roxmltree::Document::parse(raw)?;
+
+        for tu in document.descendants().filter(|n| n.has_tag_name("tu")) {
+            let segments = tu
+                .children()
+                .filter(|n| n.has_tag_name("tuv"))
+                .filter_map(|tuv| {
+                    // TMX 1.1 uses lang instead of xml:lang
+                    let language = tuv
+                        .attribute((XML_NAMESPACE, "lang"))
+                        .or(tuv.attribute("lang"))?;
+                    let seg = tuv.children().find(|n| n.has_tag_name("seg"))?;
+
+                    Some((language.to_lowercase(), text_of(seg)))
+                })
+                .collect::<Vec<_>>();
+
+            for (source_language, source) in &segments {
+                for (target_language, target) in &segments {
+                    if source_language == target_language || source.is_empty() || target.is_empty()
+                    {
+                        continue;
+                    }
+
+                    self.insert(
+                        source_language,
+                        source.clone(),
+                        target_language,
+                        target.clone(),
+                    );
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn exact_match(
+        &self,
+        source_language: &str,
+        source: &str,
+        target_language: &str,
+    ) -> Option<&str> {
+        self.matches
+            .get(&(
+                source_language.to_lowercase(),
+                source.to_string(),
+                target_language.to_lowercase(),
+            ))
+            .map(|m| m.as_str())
+    }
+
+    /// The translation of the most similar source text and its similarity percentage,
+    /// if the similarity is at least the threshold
+    pub fn best_fuzzy_match(
+        &self,
+        source_language: &str,
+        source: &str,
+        target_language: &str,
+        threshold: u8,
+    ) -> Option<(&str, u8)> {
+        let source_language = source_language.to_lowercase();
+        let target_language = target_language.to_lowercase();
+        let source = source.chars().collect::<Vec<_>>();
+
+        self.matches
+            .iter()
+            .filter(|((s, _, t), _)| s == &source_language && t == &target_language)
+            .filter_map(|((_, candidate, _), target)| {
+                let percentage = similarity(&source, candidate, threshold)?;
+
+                Some((percentage, candidate, target.as_str()))
+            })
+            // The candidate is only compared to keep the outcome stable
+            .max_by(|a, b| a.0.cmp(&b.0).then_with(|| b.1.cmp(a.1)))
+            .map(|(percentage, _, target)| (target, percentage))
+    }
+
+    /// Fills the empty translations with exact matches, returns the amount of filled rows
+    pub fn prefill(&self, rows: &mut [Row], base_language: &str, language: &str) -> i32 {
+        let mut prefilled = 0;
+
+        for row in rows {
+            if !row.translated_value.is_empty() {
+                continue;
+            }
+
+            if let Some(exact_match) = self.exact_match(base_language, &row.base_value, language) {
+                row.translated_value = exact_match.to_string();
+                row.state = PREFILLED_STATE.to_string();
+                prefilled += 1;
+            }
+        }
+
+        prefilled
+    }
+}
+
+/// Only finished work is worth reusing
+pub fn is_reusable(row: &Row) -> bool {
+    row.state == TRANSLATED_STATE && !row.translated_value.is_empty() && !row.base_value.is_empty()
+}
+
+/// The Levenshtein similarity in percentages, None if it is below the threshold
+fn similarity(source: &[char], candidate: &str, threshold: u8) -> Option<u8> {
+    let candidate = candidate.chars().collect::<Vec<_>>();
+    let longest = source.len().max(candidate.len());
+
+    if longest == 0 {
+        return None;
+    }
+
+    let percentage = |distance: usize| (100 - distance * 100 / longest) as u8;
+
+    // The distance is at least the difference in length, skip the expensive part if that is already too much
+    if percentage(source.len().abs_diff(candidate.len())) < threshold {
+        return None;
+    }
+
+    let mut previous = (0..=candidate.len()).collect::<Vec<_>>();
+    let mut current = vec![0; candidate.len() + 1];
+
+    for (i, s) in source.iter().enumerate() {
+        current[0] = i + 1;
+
+        for (j, c) in candidate.iter().enumerate() {
+            let substitution = previous[j] + usize::from(s != c);
+
+            current[j + 1] = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+        }
+
+        std::mem::swap(&mut previous, &mut current);
+    }
+
+    let percentage = percentage(previous[candidate.len()]);
+
+    if percentage >= threshold {
+        Some(percentage)
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::TranslationMemory;
+
+    #[test]
+    fn test_best_fuzzy_match() {
+        let mut translation_memory = TranslationMemory::default();
+
+        translation_memory
+            .add_from_raw(
+                "<tmx version=\"1.4\"><body><tu><tuv xml:lang=\"en\"><seg>Delete this photo</seg></tuv><tuv xml:lang=\"nl\"><seg>Verwijder deze foto</seg></tuv></tu></body></tmx>",
+            )
+            .unwrap();
+
+        assert_eq!(
+            Some(("Verwijder deze foto", 85)),
+            translation_memory.best_fuzzy_match("en", "Delete these photos", "NL", 70)
+        );
+        assert_eq!(
+            None,
+            translation_memory.best_fuzzy_match("en", "Delete these photos", "nl", 90)
+        );
+    }
+
+    #[test]
+    fn test_add_from_xcstrings_devices() {
+        let mut translation_memory = TranslationMemory::default();
+
+        translation_memory
+            .add_from_xcstrings(
+                &std::env::current_dir()
+                    .unwrap()
+                    .join("resources")
+                    .join("device_test_base.xcstrings"),
+            )
+            .unwrap();
+
+        assert_eq!(
+            Some("Klik"),
+            translation_memory.exact_match("en", "Click", "nl")
+        );
+    }
+}
@@ -0,0 +1,165 @@
+use crate::docx_writer::convert::Export;
+use crate::error::ConvertError;
+use crate::rows::{languages_to_write, rows_for_language};
+use crate::xcloc_writer::config::Config;
+use crate::xcstrings_docx_merger::convert::write_xcstrings;
+use crate::xliff_writer::convert::to_xliff;
+use serde::{Deserialize, Serialize};
+use serde_json_fmt::JsonFormat;
+use swift_localizable_json_parser::types::output::Parsed;
+
+/// Used as the source file name if the catalog isn't read from a file
+const DEFAULT_SOURCE_FILE_NAME: &str = "Localizable.xcstrings";
+pub const CONTENTS_FILE_NAME: &str = "contents.json";
+pub const LOCALIZED_CONTENTS_DIR: &str = "Localized Contents";
+pub const SOURCE_CONTENTS_DIR: &str = "Source Contents";
+
+/// The contents.json of an .xcloc bundle, the same fields Xcode writes
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Contents {
+    #[serde(rename = "developmentRegion")]
+    pub development_region: String,
+    #[serde(rename = "targetLocale")]
+    pub target_locale: String,
+    #[serde(rename = "toolInfo")]
+    pub tool_info: ToolInfo,
+    pub version: String,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ToolInfo {
+    #[serde(rename = "toolID")]
+    pub tool_id: String,
+    #[serde(rename = "toolName")]
+    pub tool_name: String,
+    #[serde(rename = "toolVersion")]
+    pub tool_version: String,
+}
+
+pub fn convert_from_path(config: Config) -> Result<Vec<Export>, ConvertError> {
+    ConvertError::ensure_exists(&config.path_to_xcstrings)?;
+
+    let read = std::fs::read(&config.path_to_xcstrings)
+        .map_err(ConvertError::io(&config.path_to_xcstrings))?;
+
+    convert_from_raw(&read, config)
+}
+
+pub fn convert_from_raw(xcstrings: &[u8], config: Config) -> Result<Vec<Export>, ConvertError> {
+    convert(
+        swift_localizable_json_parser::parse_from_bytes(xcstrings)?,
+        config,
+    )
+}
+
+pub fn convert(localizable: Parsed, config: Config) -> Result<Vec<Export>, ConvertError> {
+    let localized_per_language = localizable.localizable.localized_per_language();
+
+    if config.clean_dir_before_generating {
+        // Dir does not have to exist
+        let _ = std::fs::remove_dir_all(&config.save_in);
+    }
+
+    // Ignore any error
+    let _ = std::fs::create_dir_all(&config.save_in);
+
+    let base_language = &localizable.translation.source_language;
+    let source_file_name = config
+        .path_to_xcstrings
+        .file_name()
+        .map(|f| f.to_string_lossy().to_string())
+        .unwrap_or(DEFAULT_SOURCE_FILE_NAME.to_string());
+    let mut exports = vec![];
+
+    for language_to_write in languages_to_write(
+        &localized_per_language,
+        base_language,
+        config.new_language_codes,
+    ) {
+        log::debug!("Writing xcloc for language: {language_to_write}");
+
+        let file_name = format!("{}.xcloc", language_to_write);
+        let xcloc = config.save_in.join(&file_name);
+        let localized_contents = xcloc.join(LOCALIZED_CONTENTS_DIR);
+        let source_contents = xcloc.join(SOURCE_CONTENTS_DIR);
+
+        // Start with an empty bundle, no leftovers of a previous export
+        let _ = std::fs::remove_dir_all(&xcloc);
+
+        std::fs::create_dir_all(&localized_contents)
+            .map_err(ConvertError::io(&localized_contents))?;
+        std::fs::create_dir_all(&source_contents).map_err(ConvertError::io(&source_contents))?;
+
+        let rows = rows_for_language(&localized_per_language, base_language, &language_to_write);
+        let xliff = to_xliff(base_language, &language_to_write, &source_file_name, &rows);
+
+        let xliff_path = localized_contents.join(format!("{}.xliff", language_to_write));
+
+        std::fs::write(&xliff_path, xliff).map_err(ConvertError::io(&xliff_path))?;
+        write_xcstrings(
+            &source_contents.join(&source_file_name),
+            &localizable.translation,
+            &Default::default(),
+        )?;
+
+        let contents = Contents {
+            development_region: base_language.to_string(),
+            target_locale: language_to_write.clone(),
+            tool_info: ToolInfo {
+                tool_id: env!("CARGO_PKG_NAME").to_string(),
+                tool_name: env!("CARGO_PKG_NAME").to_string(),
+                tool_version: env!("CARGO_PKG_VERSION").to_string(),
+            },
+            version: "1.0".to_string(),
+        };
+        let json = JsonFormat::pretty()
+            .colon(" : ")
+            .unwrap() // This is fine, should always work
+            .format_to_string(&contents)?;
+
+        let contents_path = xcloc.join(CONTENTS_FILE_NAME);
+
+        std::fs::write(&contents_path, json).map_err(ConvertError::io(&contents_path))?;
+
+        exports.push(Export {
+            amount_keys_to_translate: rows.len() as i32,
+            amount_keys_total: rows.len() as i32,
+            language_code: language_to_write,
+            file_name,
+        })
+    }
+
+    Ok(exports)
+}
+
+#[cfg(test)]
+mod test {
+    use crate::xcloc_writer::config::Config;
+    use std::env::current_dir;
+
+    #[test]
+    fn test_convert() {
+        let base = current_dir().unwrap();
+        let save_in = base.join("generated").join("xcloc");
+
+        super::convert_from_path(Config {
+            save_in: save_in.clone(),
+            clean_dir_before_generating: true,
+            new_language_codes: vec!["pl".to_string()],
+            path_to_xcstrings: base.join("resources").join("reader_test_base.xcstrings"),
+        })
+        .unwrap();
+
+        let xcloc = save_in.join("pl.xcloc");
+
+        assert!(xcloc.join("Localized Contents").join("pl.xliff").exists());
+        assert!(xcloc
+            .join("Source Contents")
+            .join("reader_test_base.xcstrings")
+            .exists());
+
+        let contents = std::fs::read_to_string(xcloc.join("contents.json")).unwrap();
+
+        assert!(contents.contains("\"targetLocale\" : \"pl\""));
+    }
+}
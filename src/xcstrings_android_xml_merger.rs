@@ -0,0 +1,3 @@
+pub mod android_xml_extractor;
+pub mod config;
+pub mod convert;
@@ -0,0 +1,160 @@
+use std::path::PathBuf;
+
+use crate::error::ConvertError;
+use crate::rows::variate_from_key;
+use crate::xml::text_of;
+use swift_localizable_json_parser::types::output::PluralVariate;
+
+pub const STRINGS_XML: &str = "strings.xml";
+const VALUES_DIR_PREFIX: &str = "values";
+
+/// A single string or plural item of a strings.xml file
+#[derive(Clone, Debug)]
+pub struct AndroidString {
+    pub name: String,
+    pub variation: Option<PluralVariate>,
+    pub value: String,
+}
+
+pub fn extract(strings_xml: &PathBuf) -> Result<Vec<AndroidString>, ConvertError> {
+    if strings_xml.exists() {
+        log::debug!("strings.xml file exists...");
+    } else {
+        return Err(ConvertError::MissingFile {
+            path: strings_xml.clone(),
+        });
+    }
+
+    extract_from_raw(&std::fs::read_to_string(strings_xml).map_err(ConvertError::io(strings_xml))?)
+}
+
+pub fn extract_from_raw(raw: &str) -> Result<Vec<AndroidString>, ConvertError> {
+    let document = roxmltree::Document::parse(raw)?;
+    let mut extracted = vec![];
+
+    for node in document
+        .root_element()
+        .children()
+        .filter(|n| n.is_element())
+    {
+        let name = match node.attribute("name") {
+            None => continue,
+            Some(name) => name.to_string(),
+        };
+
+        if node.has_tag_name("string") {
+            extracted.push(AndroidString {
+                name,
+                variation: None,
+                value: unescape(&text_of(node)),
+            });
+        } else if node.has_tag_name("plurals") {
+            for item in node.children().filter(|n| n.has_tag_name("item")) {
+                let quantity = item.attribute("quantity").unwrap_or_default();
+                let variation = match variate_from_key(quantity) {
+                    None => {
+                        exit_with_log!(format!(
+                            "Unknown plural quantity {quantity} for name: {name}"
+                        ));
+                    }
+                    Some(variation) => variation,
+                };
+
+                extracted.push(AndroidString {
+                    name: name.clone(),
+                    variation: Some(variation),
+                    value: unescape(&text_of(item)),
+                });
+            }
+        }
+    }
+
+    Ok(extracted)
+}
+
+/// Reverts the escaping Android requires, e.g.: \' and \n
+pub fn unescape(value: &str) -> String {
+    // A string wrapped in double quotes is taken literally by Android
+    let value = if value.len() >= 2 && value.starts_with('"') && value.ends_with('"') {
+        &value[1..value.len() - 1]
+    } else {
+        value
+    };
+    let mut unescaped = String::with_capacity(value.len());
+    let mut chars = value.chars();
+
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            unescaped.push(c);
+
+            continue;
+        }
+
+        match chars.next() {
+            Some('n') => unescaped.push('\n'),
+            Some('t') => unescaped.push('\t'),
+            Some('u') => {
+                let hex = chars.by_ref().take(4).collect::<String>();
+
+                match u32::from_str_radix(&hex, 16).ok().and_then(char::from_u32) {
+                    Some(c) => unescaped.push(c),
+                    None => {
+                        unescaped += "\\u";
+                        unescaped += &hex;
+                    }
+                }
+            }
+            Some(c) => unescaped.push(c),
+            None => unescaped.push('\\'),
+        }
+    }
+
+    unescaped
+}
+
+/// The language code of a values directory, e.g.: values-nl -> nl, values-pt-rBR -> pt-BR.
+/// Returns None for the directory of the base language.
+pub fn language_code_of_dir(dir_name: &str) -> Option<String> {
+    let qualifier = dir_name
+        .strip_prefix(VALUES_DIR_PREFIX)?
+        .strip_prefix('-')?;
+
+    // BCP 47 qualifier, e.g.: b+sr+Latn
+    if let Some(bcp_47) = qualifier.strip_prefix("b+") {
+        return Some(bcp_47.replace('+', "-"));
+    }
+
+    let parts = qualifier
+        .split('-')
+        .map(|part| {
+            let is_region = part.len() == 3
+                && part.starts_with('r')
+                && part[1..].chars().all(|c| c.is_ascii_uppercase());
+
+            if is_region {
+                part[1..].to_string()
+            } else {
+                part.to_string()
+            }
+        })
+        .collect::<Vec<_>>();
+
+    Some(parts.join("-"))
+}
+
+#[cfg(test)]
+mod test {
+    use super::{language_code_of_dir, unescape};
+
+    #[test]
+    fn test_unescape() {
+        assert_eq!("It's %1$s", unescape("It\\'s %1$s"));
+        assert_eq!("Multi\nline", unescape("Multi\\nline"));
+        assert_eq!(" spaced ", unescape("\" spaced \""));
+        assert_eq!(
+            Some("pt-BR".to_string()),
+            language_code_of_dir("values-pt-rBR")
+        );
+        assert_eq!(None, language_code_of_dir("values"));
+    }
+}
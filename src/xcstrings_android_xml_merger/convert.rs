@@ -0,0 +1,288 @@
+use crate::error::ConvertError;
+use crate::xcstrings_android_xml_merger::android_xml_extractor::{
+    extract, language_code_of_dir, AndroidString, STRINGS_XML,
+};
+use crate::xcstrings_android_xml_merger::config::Config;
+use crate::xcstrings_docx_merger::convert::{apply_extracted, read_xcstrings, write_xcstrings};
+use crate::xcstrings_docx_merger::docx_extractor::{Extract, ExtractContainer};
+use crate::xcstrings_docx_merger::states::StateMapping;
+use regex::{Captures, Regex};
+use serde::Serialize;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::sync::LazyLock;
+use swift_localizable_json_parser::types::input;
+use swift_localizable_json_parser::types::output::{
+    Localizable, PluralVariate, SingleTranslation, Translation,
+};
+
+/// The android writer adds this string, it never has a key in the catalog
+const APP_NAME: &str = "app_name";
+
+#[derive(Clone, Debug, Serialize)]
+pub struct Export {
+    pub languages: Vec<LanguageExport>,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct LanguageExport {
+    pub language_code: String,
+    pub keys_translated: i32,
+    pub keys_to_translate: i32,
+    /// Names in the strings.xml (or other) file without a matching key in the .xcstrings file
+    pub only_in_android: Vec<String>,
+    /// Keys in the .xcstrings file without a matching name in the strings.xml (or other) file
+    pub only_in_xcstrings: Vec<String>,
+}
+
+pub fn merge(config: Config) -> Result<Export, ConvertError> {
+    let mut parsed = read_xcstrings(&config.base_xcstrings)?;
+    let source_language = parsed.translation.source_language.clone();
+    let mut strings_xmls = BTreeMap::new();
+
+    ConvertError::ensure_exists(&config.read_from)?;
+
+    let entries =
+        std::fs::read_dir(&config.read_from).map_err(ConvertError::io(&config.read_from))?;
+
+    for entry in entries {
+        let path = entry.map_err(ConvertError::io(&config.read_from))?.path();
+        let strings_xml = path.join(STRINGS_XML);
+        let language_code = path
+            .file_name()
+            .and_then(|f| language_code_of_dir(&f.to_string_lossy()));
+
+        match language_code {
+            Some(language_code) if strings_xml.exists() && language_code != source_language => {
+                strings_xmls.insert(language_code, strings_xml);
+            }
+            _ => {
+                log::debug!("Skipping: {:#?}", path);
+            }
+        }
+    }
+
+    let mut languages = vec![];
+
+    for (language_code, strings_xml) in strings_xmls {
+        log::debug!("Merging android language: {language_code}");
+
+        languages.push(merge_language(
+            &parsed.localizable,
+            &mut parsed.translation,
+            language_code,
+            extract(&strings_xml)?,
+            |value, single_translation, variation| {
+                restore_format_specifiers(
+                    value,
+                    &base_value(single_translation, &source_language, variation),
+                )
+            },
+        )?);
+    }
+
+    write_xcstrings(
+        &config.updated_xcstrings,
+        &parsed.translation,
+        &config.backups,
+    )?;
+
+    Ok(Export { languages })
+}
+
+/// Merges the strings of a single language, the names are the alphanumeric keys of the catalog.
+/// The value of every matched string goes through convert_value first.
+pub(crate) fn merge_language(
+    localizable: &Localizable,
+    translation: &mut input::Translation,
+    language_code: String,
+    android_strings: Vec<AndroidString>,
+    convert_value: impl Fn(&str, &SingleTranslation, Option<&PluralVariate>) -> String,
+) -> Result<LanguageExport, ConvertError> {
+    let mut translations_per_name: HashMap<&str, Vec<&SingleTranslation>> = HashMap::new();
+
+    for single_translation in &localizable.single_translation {
+        translations_per_name
+            .entry(single_translation.key_alphanumeric.as_str())
+            .or_default()
+            .push(single_translation);
+    }
+
+    let mut extracted = vec![];
+    let mut only_in_android = BTreeSet::new();
+    let mut merged_names = BTreeSet::new();
+
+    for android_string in android_strings {
+        let single_translation = match translations_per_name
+            .get(android_string.name.as_str())
+            .map(|t| t.as_slice())
+        {
+            None | Some([]) => {
+                if android_string.name != APP_NAME {
+                    only_in_android.insert(android_string.name);
+                }
+
+                continue;
+            }
+            Some([single_translation]) => single_translation,
+            Some(_) => {
+                log::warn!(
+                    "Multiple keys map to name: {}, skipping it",
+                    android_string.name
+                );
+
+                continue;
+            }
+        };
+
+        extracted.push(Extract {
+            key: single_translation.key_raw.clone(),
+            translated: convert_value(
+                &android_string.value,
+                single_translation,
+                android_string.variation.as_ref(),
+            ),
+            variation: android_string.variation,
+            device: None,
+            state: None,
+        });
+        merged_names.insert(android_string.name);
+    }
+
+    let only_in_xcstrings = translations_per_name
+        .iter()
+        .filter(|(name, _)| !merged_names.contains(**name))
+        .flat_map(|(_, translations)| translations.iter().map(|t| t.key_raw.clone()))
+        .collect::<BTreeSet<_>>();
+    let export = apply_extracted(
+        translation,
+        &mut Default::default(),
+        ExtractContainer {
+            language_code: language_code.clone(),
+            extracted,
+            fingerprints: None,
+        },
+        &StateMapping::default(),
+    )?;
+
+    Ok(LanguageExport {
+        language_code,
+        keys_translated: export.keys_translated,
+        keys_to_translate: export.keys_to_translate,
+        only_in_android: only_in_android.into_iter().collect(),
+        only_in_xcstrings: only_in_xcstrings.into_iter().collect(),
+    })
+}
+
+pub(crate) fn base_value(
+    single_translation: &SingleTranslation,
+    source_language: &str,
+    variation: Option<&PluralVariate>,
+) -> String {
+    match (
+        single_translation
+            .localization_value
+            .language_translation
+            .get(source_language),
+        variation,
+    ) {
+        (Some(Translation::Localization(l)), None) => l.value.clone(),
+        (Some(Translation::PluralVariation(pv)), Some(variation)) => pv
+            .iter()
+            .find(|pv| &pv.variate == variation)
+            .or(pv.last())
+            .map(|pv| pv.translation_value.value.clone())
+            .unwrap_or_default(),
+        _ => Default::default(),
+    }
+}
+
+static POSITIONAL_SPECIFIER_REGEX: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"%(\d+)\$(@|l{0,2}[di]|s)").unwrap());
+
+/// The android writer turns %1$lld into %1$d and translators tend to use %1$s for %1$@,
+/// put back the specifier the base language uses
+fn restore_format_specifiers(value: &str, base_value: &str) -> String {
+    let base_specifiers = POSITIONAL_SPECIFIER_REGEX
+        .captures_iter(base_value)
+        .map(|c| (c[1].to_string(), c[2].to_string()))
+        .collect::<HashMap<_, _>>();
+
+    POSITIONAL_SPECIFIER_REGEX
+        .replace_all(value, |c: &Captures| {
+            let position = &c[1];
+            let specifier = &c[2];
+
+            match base_specifiers.get(position) {
+                Some(base)
+                    if (specifier == "s" && base == "@")
+                        || (specifier.ends_with(['d', 'i']) && base.ends_with(['d', 'i'])) =>
+                {
+                    format!("%{position}${base}")
+                }
+                _ => c[0].to_string(),
+            }
+        })
+        .to_string()
+}
+
+#[cfg(test)]
+mod test {
+    use crate::xcstrings_android_xml_merger::config::Config;
+    use crate::xcstrings_android_xml_merger::convert::merge;
+    use std::env::current_dir;
+
+    #[test]
+    fn test_merge() {
+        let base = current_dir().unwrap();
+        let save_in = base.join("generated").join("android_merge");
+        let res = save_in.join("res");
+        let xcstrings_updated = save_in.join("updated.xcstrings");
+
+        let _ = std::fs::remove_dir_all(&save_in);
+
+        std::fs::create_dir_all(&res).unwrap();
+
+        crate::android_xml_writer::convert::write_xmls(crate::android_xml_writer::config::Config {
+            base_xcstrings: base.join("resources").join("reader_test_updated.xcstrings"),
+            write_in: res.clone(),
+            app_name_for_android: "test_app".to_string(),
+        })
+        .unwrap();
+
+        // Android translators fix translations directly in the strings.xml
+        let nl = res.join("values-nl").join("strings.xml");
+        let raw = std::fs::read_to_string(&nl).unwrap().replace(
+            "<string name=\"normal_0\">updated</string>",
+            "<string name=\"normal_0\">It\\'s fixed</string>\n<string name=\"only_android\">x</string>",
+        );
+
+        std::fs::write(&nl, raw).unwrap();
+
+        let export = merge(Config {
+            read_from: res,
+            base_xcstrings: base.join("resources").join("reader_test_base.xcstrings"),
+            updated_xcstrings: xcstrings_updated.clone(),
+            backups: Default::default(),
+        })
+        .unwrap();
+        let nl = export
+            .languages
+            .iter()
+            .find(|l| l.language_code == "nl")
+            .unwrap();
+        let pl = export
+            .languages
+            .iter()
+            .find(|l| l.language_code == "pl")
+            .unwrap();
+
+        assert_eq!(vec!["only_android".to_string()], nl.only_in_android);
+        assert!(nl.only_in_xcstrings.is_empty());
+        assert_eq!(4, pl.only_in_xcstrings.len());
+
+        let updated = std::fs::read_to_string(xcstrings_updated).unwrap();
+
+        assert!(updated.contains("\"value\" : \"It's fixed\""));
+        assert!(updated.contains("\"value\" : \"%1$lld sdsad\""));
+    }
+}
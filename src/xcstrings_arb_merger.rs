@@ -0,0 +1,3 @@
+pub mod arb_extractor;
+pub mod config;
+pub mod convert;
@@ -0,0 +1,168 @@
+use std::path::PathBuf;
+use std::sync::LazyLock;
+
+use crate::arb_writer::convert::{ICU_ZERO, LOCALE_KEY, METADATA_PREFIX};
+use crate::error::ConvertError;
+use crate::rows::variate_from_key;
+use crate::xcstrings_android_xml_merger::android_xml_extractor::AndroidString;
+use regex::Regex;
+use serde_json::{Map, Value};
+use swift_localizable_json_parser::types::output::PluralVariate;
+
+pub const ARB_EXTENSION: &str = "arb";
+
+/// The messages of a single ARB file, the values still contain the ICU placeholders
+#[derive(Clone, Debug)]
+pub struct ArbFile {
+    pub language_code: String,
+    pub strings: Vec<AndroidString>,
+}
+
+pub fn extract(arb: &PathBuf) -> Result<ArbFile, ConvertError> {
+    if arb.exists() {
+        log::debug!("arb file exists...");
+    } else {
+        return Err(ConvertError::MissingFile { path: arb.clone() });
+    }
+
+    // Without @@locale, Flutter takes the locale from the file name, e.g.: app_pt_BR.arb
+    let locale_of_file_name = arb.file_stem().and_then(|s| {
+        s.to_string_lossy()
+            .split_once('_')
+            .map(|(_, l)| l.to_string())
+    });
+
+    extract_from_raw(
+        &std::fs::read_to_string(arb).map_err(ConvertError::io(arb))?,
+        locale_of_file_name,
+    )
+}
+
+pub fn extract_from_raw(
+    raw: &str,
+    locale_of_file_name: Option<String>,
+) -> Result<ArbFile, ConvertError> {
+    let arb: Map<String, Value> = serde_json::from_str(raw)?;
+    let locale = match arb
+        .get(LOCALE_KEY)
+        .and_then(|l| l.as_str())
+        .map(|l| l.to_string())
+        .or(locale_of_file_name)
+    {
+        None => {
+            exit_with_log!("There is no @@locale in the arb file");
+        }
+        Some(locale) => locale,
+    };
+    let mut strings = vec![];
+
+    for (name, value) in arb {
+        if name.starts_with(METADATA_PREFIX) {
+            continue;
+        }
+
+        let value = match value {
+            Value::String(value) => value,
+            _ => {
+                exit_with_log!(format!("Expected a string message for: {name}"));
+            }
+        };
+
+        match plural_cases(&value)? {
+            None => strings.push(AndroidString {
+                name,
+                variation: None,
+                value,
+            }),
+            Some(cases) => {
+                for (variation, value) in cases {
+                    strings.push(AndroidString {
+                        name: name.clone(),
+                        variation: Some(variation),
+                        value,
+                    });
+                }
+            }
+        }
+    }
+
+    Ok(ArbFile {
+        language_code: locale.replace('_', "-"),
+        strings,
+    })
+}
+
+/// The start of an ICU plural, e.g.: {count, plural,
+static PLURAL_REGEX: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"\{\s*\w+\s*,\s*plural\s*,").unwrap());
+
+/// The cases of an ICU plural message, text around the plural ends up in every case
+fn plural_cases(message: &str) -> Result<Option<Vec<(PluralVariate, String)>>, ConvertError> {
+    let found = match PLURAL_REGEX.find(message) {
+        None => return Ok(None),
+        Some(found) => found,
+    };
+    let prefix = &message[..found.start()];
+    let rest = &message[found.end()..];
+    let mut chars = rest.char_indices();
+    let mut selector = String::new();
+    let mut cases = vec![];
+    let suffix_start = loop {
+        match chars.next() {
+            None => {
+                exit_with_log!(format!("Unterminated plural in message: {message}"));
+            }
+            // The end of the plural
+            Some((index, '}')) => break index + 1,
+            Some((_, '{')) => {
+                let mut depth = 1;
+                let mut case = String::new();
+
+                for (_, c) in chars.by_ref() {
+                    match c {
+                        '{' => depth += 1,
+                        '}' => depth -= 1,
+                        _ => {}
+                    }
+
+                    if depth == 0 {
+                        break;
+                    }
+
+                    case.push(c);
+                }
+
+                if depth != 0 {
+                    exit_with_log!(format!("Unterminated plural case in message: {message}"));
+                }
+
+                let variation = match selector.trim() {
+                    ICU_ZERO => Some(PluralVariate::Zero),
+                    "=1" => Some(PluralVariate::One),
+                    selector => variate_from_key(selector),
+                };
+
+                match variation {
+                    None => {
+                        exit_with_log!(format!(
+                            "Unsupported plural selector {} in: {message}",
+                            selector.trim()
+                        ));
+                    }
+                    Some(variation) => cases.push((variation, case)),
+                }
+
+                selector.clear();
+            }
+            Some((_, c)) => selector.push(c),
+        }
+    };
+    let suffix = &rest[suffix_start..];
+
+    Ok(Some(
+        cases
+            .into_iter()
+            .map(|(variation, case)| (variation, format!("{prefix}{case}{suffix}")))
+            .collect(),
+    ))
+}
@@ -0,0 +1,18 @@
+use crate::backup::Backups;
+use clap::Parser;
+use std::path::PathBuf;
+
+#[derive(Clone, Debug, Parser)]
+pub struct Config {
+    /// The directory which contains the .arb files
+    #[clap(long)]
+    pub read_from: PathBuf,
+    /// The path to an existing .xcstrings file to merge the localizations from
+    #[clap(long)]
+    pub base_xcstrings: PathBuf,
+    /// The path to an existing or non-existing .xcstrings file to merge the localizations to
+    #[clap(long)]
+    pub updated_xcstrings: PathBuf,
+    #[clap(flatten)]
+    pub backups: Backups,
+}
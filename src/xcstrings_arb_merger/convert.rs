@@ -0,0 +1,114 @@
+use crate::error::ConvertError;
+use crate::placeholders::{from_named, plural_position};
+use crate::xcstrings_android_xml_merger::convert::{base_value, merge_language, Export};
+use crate::xcstrings_arb_merger::arb_extractor::{extract, ARB_EXTENSION};
+use crate::xcstrings_arb_merger::config::Config;
+use crate::xcstrings_docx_merger::convert::{read_xcstrings, write_xcstrings};
+
+pub fn merge(config: Config) -> Result<Export, ConvertError> {
+    let mut parsed = read_xcstrings(&config.base_xcstrings)?;
+    let source_language = parsed.translation.source_language.clone();
+    let mut arbs = vec![];
+
+    ConvertError::ensure_exists(&config.read_from)?;
+
+    let entries =
+        std::fs::read_dir(&config.read_from).map_err(ConvertError::io(&config.read_from))?;
+
+    for entry in entries {
+        let path = entry.map_err(ConvertError::io(&config.read_from))?.path();
+
+        if path.extension().map(|e| e == ARB_EXTENSION) == Some(true) {
+            arbs.push(path);
+        }
+    }
+
+    arbs.sort();
+
+    let mut languages = vec![];
+
+    for arb in arbs {
+        let arb_file = extract(&arb)?;
+
+        // The template is generated from the catalog
+        if arb_file.language_code == source_language {
+            log::debug!("Skipping: {:#?}", arb);
+
+            continue;
+        }
+
+        log::debug!("Merging arb language: {}", arb_file.language_code);
+
+        languages.push(merge_language(
+            &parsed.localizable,
+            &mut parsed.translation,
+            arb_file.language_code,
+            arb_file.strings,
+            |value, single_translation, variation| {
+                from_named(
+                    value,
+                    &base_value(single_translation, &source_language, variation),
+                    plural_position(single_translation, &source_language),
+                    "{",
+                    "}",
+                )
+            },
+        )?);
+    }
+
+    write_xcstrings(
+        &config.updated_xcstrings,
+        &parsed.translation,
+        &config.backups,
+    )?;
+
+    Ok(Export { languages })
+}
+
+#[cfg(test)]
+mod test {
+    use crate::xcstrings_arb_merger::config::Config;
+    use crate::xcstrings_arb_merger::convert::merge;
+    use std::env::current_dir;
+
+    #[test]
+    fn test_merge() {
+        let base = current_dir().unwrap();
+        let save_in = base.join("generated").join("arb_merge");
+        let arbs = save_in.join("l10n");
+        let xcstrings_updated = save_in.join("updated.xcstrings");
+
+        let _ = std::fs::remove_dir_all(&save_in);
+
+        crate::arb_writer::convert::write_arbs(crate::arb_writer::config::Config {
+            path_to_xcstrings: base.join("resources").join("reader_test_base.xcstrings"),
+            write_in: arbs.clone(),
+            file_prefix: "app".to_string(),
+        })
+        .unwrap();
+
+        // Translators complete the plural and reorder the placeholders
+        let nl = arbs.join("app_nl.arb");
+        let raw = std::fs::read_to_string(&nl).unwrap().replace(
+            "\"plurals\": \"{count, plural, one{{count} eentje}}\"",
+            "\"plurals\": \"{count, plural, one{{count} eentje} other{{count} meerdere}}\",\n  \"inlined_with_an_inlined_base_language\": \"({p2}%) {p1} sleutels\"",
+        );
+
+        std::fs::write(&nl, raw).unwrap();
+
+        let export = merge(Config {
+            read_from: arbs,
+            base_xcstrings: base.join("resources").join("reader_test_base.xcstrings"),
+            updated_xcstrings: xcstrings_updated.clone(),
+            backups: Default::default(),
+        })
+        .unwrap();
+
+        assert_eq!(1, export.languages.len());
+
+        let updated = std::fs::read_to_string(xcstrings_updated).unwrap();
+
+        assert!(updated.contains("\"value\" : \"%1$lld meerdere\""));
+        assert!(updated.contains("\"value\" : \"(%2$lld%%) %1$lld sleutels\""));
+    }
+}
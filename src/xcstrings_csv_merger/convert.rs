@@ -0,0 +1,54 @@
+use crate::error::ConvertError;
+use crate::xcstrings_csv_merger::config::Config;
+use crate::xcstrings_csv_merger::csv_extractor::extract;
+use crate::xcstrings_docx_merger::convert::{merge_extracted, Export};
+
+pub fn merge(config: Config) -> Result<Export, ConvertError> {
+    let extracted = extract(&config.extract_from_csv)?;
+
+    merge_extracted(
+        &config.base_xcstrings,
+        &config.updated_xcstrings,
+        &config.backups,
+        extracted,
+    )
+}
+
+#[cfg(test)]
+mod test {
+    use crate::xcstrings_csv_merger::config::Config;
+    use crate::xcstrings_csv_merger::convert::merge;
+    use std::env::current_dir;
+
+    #[test]
+    fn test_merge() {
+        let base = current_dir().unwrap();
+        let save_in = base.join("generated").join("csv_merge");
+        let xcstrings = base.join("resources").join("reader_test_base.xcstrings");
+        let xcstrings_updated = save_in.join("updated.xcstrings");
+
+        crate::csv_writer::convert::convert_from_path(crate::csv_writer::config::Config {
+            save_in: save_in.clone(),
+            clean_dir_before_generating: true,
+            new_language_codes: vec![],
+            path_to_xcstrings: xcstrings.clone(),
+            columns_in_output: vec![crate::docx_writer::config::Column::State],
+            delimiter: crate::csv_writer::config::Delimiter::Comma,
+        })
+        .unwrap();
+
+        let export = merge(Config {
+            extract_from_csv: save_in.join("nl.csv"),
+            base_xcstrings: xcstrings,
+            updated_xcstrings: xcstrings_updated.clone(),
+            backups: Default::default(),
+        })
+        .unwrap();
+
+        assert_eq!(4, export.keys_translated);
+        assert_eq!(4, export.keys_to_translate);
+        assert!(std::fs::read_to_string(xcstrings_updated)
+            .unwrap()
+            .contains("\"value\" : \"Ingelijnd\\nmet \\n\\n newlines\""));
+    }
+}
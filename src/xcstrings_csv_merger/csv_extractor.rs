@@ -0,0 +1,49 @@
+use std::path::PathBuf;
+
+use crate::csv_writer::config::Delimiter;
+use crate::error::ConvertError;
+use crate::xcstrings_docx_merger::docx_extractor::{extract_from_rows, ExtractContainer};
+
+pub fn extract(extract_from_csv: &PathBuf) -> Result<Vec<ExtractContainer>, ConvertError> {
+    if extract_from_csv.exists() {
+        log::debug!("csv file exists...");
+    } else {
+        return Err(ConvertError::MissingFile {
+            path: extract_from_csv.clone(),
+        });
+    }
+
+    let delimiter = if extract_from_csv
+        .extension()
+        .map(|e| e.eq_ignore_ascii_case(Delimiter::Tab.extension()))
+        .unwrap_or(false)
+    {
+        Delimiter::Tab
+    } else {
+        Delimiter::Comma
+    };
+    let raw =
+        std::fs::read_to_string(extract_from_csv).map_err(ConvertError::io(extract_from_csv))?;
+
+    extract_from_raw(&raw, delimiter)
+}
+
+pub fn extract_from_raw(
+    raw: &str,
+    delimiter: Delimiter,
+) -> Result<Vec<ExtractContainer>, ConvertError> {
+    // Spreadsheet programs like to add a byte order mark, which would end up in the Key header
+    let raw = raw.trim_start_matches('\u{feff}');
+    let mut reader = csv::ReaderBuilder::new()
+        .delimiter(delimiter.byte())
+        .has_headers(false)
+        .flexible(true)
+        .from_reader(raw.as_bytes());
+    let mut rows = vec![];
+
+    for record in reader.records() {
+        rows.push(record?.iter().map(|r| r.to_string()).collect::<Vec<_>>());
+    }
+
+    extract_from_rows(rows, &[])
+}
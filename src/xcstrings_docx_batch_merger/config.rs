@@ -0,0 +1,38 @@
+use crate::backup::Backups;
+use crate::xcstrings_docx_merger::config::{ConflictPolicy, PlaceholderPolicy, StatePrecedence};
+use clap::Parser;
+use std::path::PathBuf;
+
+#[derive(Clone, Debug, Parser)]
+pub struct Config {
+    /// The docx files to read from, every docx file of a directory is read
+    #[clap(long, required = true)]
+    pub extract_from_docx: Vec<PathBuf>,
+    /// The path to an existing .xcstrings file to merge the localizations from
+    #[clap(long)]
+    pub base_xcstrings: PathBuf,
+    /// The path to an existing or non-existing .xcstrings file to merge the localizations to
+    #[clap(long)]
+    pub updated_xcstrings: PathBuf,
+    #[clap(flatten)]
+    pub backups: Backups,
+    /// Validates every docx file without writing the catalog
+    #[clap(long)]
+    pub dry_run: bool,
+    /// What to do with rows whose base value or translation changed in the catalog after the docx was written
+    #[clap(long, value_enum, default_value_t = ConflictPolicy::Skip)]
+    pub conflict_policy: ConflictPolicy,
+    /// What to do with translations whose placeholders don't match the base value, e.g. a dropped %1$lld
+    #[clap(long, value_enum, default_value_t = PlaceholderPolicy::Warn)]
+    pub placeholder_policy: PlaceholderPolicy,
+    /// The state of changed translations without a state in the State column, e.g. needs_review for vendor imports.
+    /// Unchanged translations keep the state of the catalog
+    #[clap(long, default_value = "translated")]
+    pub import_state: String,
+    /// Which state changed translations get when the State column has one as well
+    #[clap(long, value_enum, default_value_t = StatePrecedence::RowState)]
+    pub state_precedence: StatePrecedence,
+    /// Renames the states of the State column before merging, formatted as from=to, e.g. approved=translated
+    #[clap(long)]
+    pub state_mapping: Vec<String>,
+}
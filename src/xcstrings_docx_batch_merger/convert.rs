@@ -0,0 +1,268 @@
+use crate::error::ConvertError;
+use crate::xcstrings_docx_batch_merger::config::Config;
+use crate::xcstrings_docx_merger::conflicts::Conflict;
+use crate::xcstrings_docx_merger::convert::{
+    merge_containers, read_xcstrings_with_devices, write_xcstrings_with_devices,
+};
+use crate::xcstrings_docx_merger::docx_extractor::{extract, ExtractContainer};
+use crate::xcstrings_docx_merger::states::StateMapping;
+use crate::xcstrings_docx_merger::validation::{PlaceholderProblem, PluralProblem};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+#[derive(Clone, Debug, Serialize)]
+pub struct Export {
+    pub keys_translated: i32,
+    pub keys_to_translate: i32,
+    pub files: Vec<FileExport>,
+}
+
+/// The stats of a single docx file
+#[derive(Clone, Debug, Serialize)]
+pub struct FileExport {
+    pub file: PathBuf,
+    pub language_codes: Vec<String>,
+    pub keys_translated: i32,
+    pub keys_to_translate: i32,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub conflicts: Vec<Conflict>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub placeholder_problems: Vec<PlaceholderProblem>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub plural_problems: Vec<PluralProblem>,
+}
+
+/// Merges all docx files in the catalog, which is read and written once.
+/// Every file is extracted and applied before writing, so an invalid file leaves the catalog untouched.
+pub fn merge(config: Config) -> Result<Export, ConvertError> {
+    let state_mapping = StateMapping::new(
+        &config.import_state,
+        config.state_precedence,
+        &config.state_mapping,
+    )?;
+    let files = docx_files(&config.extract_from_docx)?;
+    let mut extracted_per_file = vec![];
+
+    for file in files {
+        log::debug!("Extracting: {:#?}", file);
+
+        let containers = extract(&file)?;
+
+        extracted_per_file.push((file, containers));
+    }
+
+    validate_unique(&extracted_per_file)?;
+
+    let (mut parsed, mut devices) = read_xcstrings_with_devices(&config.base_xcstrings)?;
+    let mut export = Export {
+        keys_translated: 0,
+        keys_to_translate: 0,
+        files: vec![],
+    };
+
+    for (file, containers) in extracted_per_file {
+        let language_codes = containers.iter().map(|c| c.language_code.clone()).collect();
+        let merged = merge_containers(
+            &mut parsed,
+            &mut devices,
+            containers,
+            config.conflict_policy,
+            config.placeholder_policy,
+            &state_mapping,
+            None,
+        )
+        .map_err(ConvertError::in_file(&file))?;
+
+        export.keys_translated += merged.keys_translated;
+        export.keys_to_translate += merged.keys_to_translate;
+        export.files.push(FileExport {
+            file,
+            language_codes,
+            keys_translated: merged.keys_translated,
+            keys_to_translate: merged.keys_to_translate,
+            conflicts: merged.conflicts,
+            placeholder_problems: merged.placeholder_problems,
+            plural_problems: merged.plural_problems,
+        });
+    }
+
+    if config.dry_run {
+        log::debug!("Dry run, not writing to: {:#?}", config.updated_xcstrings);
+    } else {
+        write_xcstrings_with_devices(
+            &config.updated_xcstrings,
+            &parsed.translation,
+            &devices,
+            &config.backups,
+        )?;
+    }
+
+    Ok(export)
+}
+
+/// Directories are expanded to their docx files, sorted by name.
+/// Lock files of Word (~$name.docx) are skipped.
+fn docx_files(paths: &[PathBuf]) -> Result<Vec<PathBuf>, ConvertError> {
+    let mut files = vec![];
+
+    for path in paths {
+        if !path.is_dir() {
+            files.push(path.clone());
+
+            continue;
+        }
+
+        let mut in_dir = vec![];
+
+        for entry in std::fs::read_dir(path).map_err(ConvertError::io(path))? {
+            let entry = entry.map_err(ConvertError::io(path))?.path();
+            let is_docx = entry.extension().map(|e| e == "docx") == Some(true);
+            let is_lock_file = entry
+                .file_name()
+                .map(|f| f.to_string_lossy().starts_with("~$"))
+                == Some(true);
+
+            if is_docx && !is_lock_file {
+                in_dir.push(entry);
+            }
+        }
+
+        in_dir.sort();
+        files.extend(in_dir);
+    }
+
+    if files.is_empty() {
+        exit_with_log!("There are no docx files to merge");
+    }
+
+    Ok(files)
+}
+
+/// The same row of a language in multiple files is ambiguous, the merge order would decide the translation
+fn validate_unique(
+    extracted_per_file: &[(PathBuf, Vec<ExtractContainer>)],
+) -> Result<(), ConvertError> {
+    let mut seen: HashMap<(&str, String), usize> = HashMap::new();
+
+    for (index, (file, containers)) in extracted_per_file.iter().enumerate() {
+        for container in containers {
+            for extract in &container.extracted {
+                let unit_id = extract.unit_id();
+
+                if let Some(other) =
+                    seen.insert((container.language_code.as_str(), unit_id.clone()), index)
+                {
+                    if other != index {
+                        return Err(ConvertError::from(format!(
+                            "{unit_id} of language {} is in both {} and {}",
+                            container.language_code,
+                            extracted_per_file[other].0.display(),
+                            file.display()
+                        )));
+                    }
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use crate::devices::DeviceVariate;
+    use crate::xcstrings_docx_batch_merger::config::Config;
+    use crate::xcstrings_docx_batch_merger::convert::{merge, validate_unique};
+    use crate::xcstrings_docx_merger::config::{ConflictPolicy, PlaceholderPolicy};
+    use crate::xcstrings_docx_merger::docx_extractor::{Extract, ExtractContainer};
+    use std::env::current_dir;
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_batch_merge() {
+        let base = current_dir().unwrap();
+        let resources = base.join("resources");
+        let save_in = base.join("generated").join("batch");
+        let updated_xcstrings = save_in.join("updated.xcstrings");
+        let config = |extract_from_docx| Config {
+            extract_from_docx,
+            base_xcstrings: resources.join("reader_test_base.xcstrings"),
+            updated_xcstrings: updated_xcstrings.clone(),
+            backups: Default::default(),
+            dry_run: false,
+            conflict_policy: ConflictPolicy::Skip,
+            placeholder_policy: PlaceholderPolicy::Warn,
+            import_state: "translated".to_string(),
+            state_precedence: Default::default(),
+            state_mapping: vec![],
+        };
+
+        let _ = std::fs::remove_dir_all(&save_in);
+        std::fs::create_dir_all(&save_in).unwrap();
+
+        // The same file twice is ambiguous, nothing is written
+        let duplicate = merge(config(vec![
+            resources.join("reader_test_updated_nl.docx"),
+            resources.join("reader_test_updated_nl.docx"),
+        ]));
+
+        assert!(duplicate.is_err());
+        assert!(!updated_xcstrings.exists());
+
+        for language in ["nl", "pl"] {
+            std::fs::copy(
+                resources.join(format!("reader_test_updated_{language}.docx")),
+                save_in.join(format!("{language}.docx")),
+            )
+            .unwrap();
+        }
+
+        let export = merge(config(vec![save_in.clone()])).unwrap();
+
+        assert_eq!(
+            vec!["nl", "pl"],
+            export
+                .files
+                .iter()
+                .flat_map(|f| f.language_codes.clone())
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(
+            std::fs::read_to_string(resources.join("reader_test_updated.xcstrings")).unwrap(),
+            std::fs::read_to_string(&updated_xcstrings).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_validate_unique_devices() {
+        let file = |name: &str, device| {
+            (
+                PathBuf::from(name),
+                vec![ExtractContainer {
+                    language_code: "nl".to_string(),
+                    extracted: vec![Extract {
+                        key: "tap".to_string(),
+                        variation: None,
+                        device: Some(device),
+                        translated: "Tik".to_string(),
+                        state: None,
+                    }],
+                    fingerprints: None,
+                }],
+            )
+        };
+
+        // The device variations of a key are different rows
+        assert!(validate_unique(&[
+            file("iphone.docx", DeviceVariate::IPhone),
+            file("mac.docx", DeviceVariate::Mac),
+        ])
+        .is_ok());
+        assert!(validate_unique(&[
+            file("a.docx", DeviceVariate::Mac),
+            file("b.docx", DeviceVariate::Mac),
+        ])
+        .is_err());
+    }
+}
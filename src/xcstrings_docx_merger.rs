@@ -1,3 +1,7 @@
 pub mod config;
+pub mod conflicts;
 pub mod convert;
+pub mod diff;
 pub mod docx_extractor;
+pub mod states;
+pub mod validation;
@@ -1,3 +1,4 @@
+use crate::backup::Backups;
 use clap::Parser;
 use std::path::PathBuf;
 
@@ -12,4 +13,56 @@ pub struct Config {
     /// The path to an existing or non-existing .xcstrings file to merge the localizations to
     #[clap(long)]
     pub updated_xcstrings: PathBuf,
+    #[clap(flatten)]
+    pub backups: Backups,
+    /// Writes nothing, the export contains the changes the merge would make
+    #[clap(long)]
+    pub dry_run: bool,
+    /// Writes the changes as a readable report, as HTML if the extension is html, else as a plain text table
+    #[clap(long)]
+    pub diff_report: Option<PathBuf>,
+    /// What to do with rows whose base value or translation changed in the catalog after the docx was written
+    #[clap(long, value_enum, default_value_t = ConflictPolicy::Skip)]
+    pub conflict_policy: ConflictPolicy,
+    /// What to do with translations whose placeholders don't match the base value, e.g. a dropped %1$lld
+    #[clap(long, value_enum, default_value_t = PlaceholderPolicy::Warn)]
+    pub placeholder_policy: PlaceholderPolicy,
+    /// The state of changed translations without a state in the State column, e.g. needs_review for vendor imports.
+    /// Unchanged translations keep the state of the catalog
+    #[clap(long, default_value = "translated")]
+    pub import_state: String,
+    /// Which state changed translations get when the State column has one as well
+    #[clap(long, value_enum, default_value_t = StatePrecedence::RowState)]
+    pub state_precedence: StatePrecedence,
+    /// Renames the states of the State column before merging, formatted as from=to, e.g. approved=translated
+    #[clap(long)]
+    pub state_mapping: Vec<String>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, clap::ValueEnum)]
+pub enum ConflictPolicy {
+    /// Keeps the catalog as is for the conflicting rows
+    Skip,
+    Overwrite,
+    /// Merges the conflicting rows with the needs_review state
+    NeedsReview,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum StatePrecedence {
+    /// The state of the State column, the import state is only used for rows without one
+    #[default]
+    RowState,
+    /// The import state, e.g. to review every changed translation of a vendor regardless of the State column
+    ImportState,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, clap::ValueEnum)]
+pub enum PlaceholderPolicy {
+    /// Fails the merge, nothing is written
+    Fail,
+    /// Merges the translations as is, the problems are part of the output
+    Warn,
+    /// Merges the translations with the needs_review state
+    NeedsReview,
 }
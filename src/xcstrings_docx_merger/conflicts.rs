@@ -0,0 +1,184 @@
+use crate::devices::{rows_with_devices, DeviceVariations};
+use crate::fingerprint::{fingerprint, Fingerprints};
+use crate::xcstrings_docx_merger::config::ConflictPolicy;
+use crate::xcstrings_docx_merger::docx_extractor::ExtractContainer;
+use crate::NEEDS_REVIEW_STATE;
+use serde::Serialize;
+use swift_localizable_json_parser::types::output::Parsed;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ConflictReason {
+    /// The base value changed after the document was written, the translation is probably outdated
+    StaleSource,
+    /// The translation in the catalog changed after the document was written
+    EditedTarget,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct Conflict {
+    pub language_code: String,
+    pub key: String,
+    pub variation: Option<String>,
+    pub reason: ConflictReason,
+}
+
+/// Compares the fingerprints of the document with the catalog and applies the policy to the conflicting rows.
+/// A row only conflicts if the translator changed it as well, untouched rows keep the catalog as is.
+/// Documents without fingerprints never conflict.
+pub(crate) fn resolve_conflicts(
+    parsed: &Parsed,
+    devices: &DeviceVariations,
+    extracted: &mut ExtractContainer,
+    policy: ConflictPolicy,
+) -> Vec<Conflict> {
+    let exported = match extracted.fingerprints.take() {
+        None => return vec![],
+        Some(fingerprints) => fingerprints,
+    };
+    let current = Fingerprints::from_rows(&rows_with_devices(
+        &parsed.localizable.localized_per_language(),
+        &parsed.translation.source_language,
+        devices,
+        &extracted.language_code,
+    ));
+    let mut conflicts = vec![];
+
+    extracted.extracted.retain_mut(|extract| {
+        let unit_id = extract.unit_id();
+        let (exported, current) = match (exported.0.get(&unit_id), current.0.get(&unit_id)) {
+            (Some(exported), Some(current)) => (exported, current),
+            _ => return true,
+        };
+        let reason = if exported.base != current.base {
+            ConflictReason::StaleSource
+        } else if exported.translation != current.translation {
+            ConflictReason::EditedTarget
+        } else {
+            return true;
+        };
+
+        if fingerprint(&extract.translated) == exported.translation {
+            log::debug!(
+                "Keeping the catalog for the untouched row {unit_id}: {:?}",
+                reason
+            );
+
+            return false;
+        }
+
+        log::debug!("Conflict for {unit_id}: {:?}", reason);
+
+        conflicts.push(Conflict {
+            language_code: extracted.language_code.clone(),
+            key: extract.key.clone(),
+            variation: extract.variation_text(),
+            reason,
+        });
+
+        match policy {
+            ConflictPolicy::Skip => false,
+            ConflictPolicy::Overwrite => true,
+            ConflictPolicy::NeedsReview => {
+                extract.state = Some(NEEDS_REVIEW_STATE.to_string());
+
+                true
+            }
+        }
+    });
+
+    conflicts
+}
+
+#[cfg(test)]
+mod test {
+    use crate::xcstrings_docx_merger::config::{Config, ConflictPolicy};
+    use crate::xcstrings_docx_merger::conflicts::{resolve_conflicts, ConflictReason};
+    use crate::xcstrings_docx_merger::convert::{merge, read_xcstrings_with_devices};
+    use crate::xcstrings_docx_merger::docx_extractor::extract;
+    use std::env::current_dir;
+
+    #[test]
+    fn test_conflicts() {
+        let base = current_dir().unwrap();
+        let save_in = base.join("generated").join("conflicts");
+        let xcstrings = base.join("resources").join("reader_test_base.xcstrings");
+        let edited = save_in.join("edited.xcstrings");
+        let updated = save_in.join("updated.xcstrings");
+        let nl = save_in.join("nl.docx");
+
+        crate::docx_writer::convert::convert_from_path(crate::docx_writer::config::Config {
+            save_in: save_in.clone(),
+            clean_dir_before_generating: true,
+            new_language_codes: vec![],
+            path_to_xcstrings: xcstrings.clone(),
+            columns_in_output: vec![],
+            translation_memories: vec![],
+            suggestion_catalogs: vec![],
+            suggestion_threshold: 70,
+            language_groups: vec![],
+            filter: Default::default(),
+        })
+        .unwrap();
+
+        // A developer fixes the translation while the document is at the translator
+        let raw = std::fs::read_to_string(&xcstrings)
+            .unwrap()
+            .replace("\"value\" : \"normaal\"", "\"value\" : \"Normaal\"");
+
+        std::fs::write(&edited, raw).unwrap();
+
+        // The translator didn't touch the row, so even overwriting keeps the fix of the developer
+        let export = merge(Config {
+            extract_from_docx: nl.clone(),
+            base_xcstrings: edited.clone(),
+            updated_xcstrings: updated.clone(),
+            backups: Default::default(),
+            dry_run: false,
+            diff_report: None,
+            conflict_policy: ConflictPolicy::Overwrite,
+            placeholder_policy: crate::xcstrings_docx_merger::config::PlaceholderPolicy::Warn,
+            import_state: "translated".to_string(),
+            state_precedence: Default::default(),
+            state_mapping: vec![],
+        })
+        .unwrap();
+
+        assert!(export.conflicts.is_empty());
+        assert!(std::fs::read_to_string(&updated)
+            .unwrap()
+            .contains("\"value\" : \"Normaal\""));
+
+        // The translator changed the row as well
+        let (parsed, devices) = read_xcstrings_with_devices(&edited).unwrap();
+        let resolve = |policy| {
+            let mut extracted = extract(&nl).unwrap().remove(0);
+
+            extracted
+                .extracted
+                .iter_mut()
+                .find(|e| e.key == "normal_0")
+                .unwrap()
+                .translated = "normaal!".to_string();
+
+            let conflicts = resolve_conflicts(&parsed, &devices, &mut extracted, policy);
+
+            (conflicts, extracted)
+        };
+        let (conflicts, extracted) = resolve(ConflictPolicy::Skip);
+
+        assert_eq!(1, conflicts.len());
+        assert_eq!("normal_0", conflicts[0].key);
+        assert_eq!(ConflictReason::EditedTarget, conflicts[0].reason);
+        assert!(!extracted.extracted.iter().any(|e| e.key == "normal_0"));
+
+        let (_, extracted) = resolve(ConflictPolicy::NeedsReview);
+        let normal_0 = extracted
+            .extracted
+            .iter()
+            .find(|e| e.key == "normal_0")
+            .unwrap();
+
+        assert_eq!(Some("needs_review"), normal_0.state.as_deref());
+    }
+}
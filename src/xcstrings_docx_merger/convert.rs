@@ -1,61 +1,324 @@
 use docx_rust::document::{ParagraphContent, RunContent, TableCellContent, TableRowContent};
 use std::default::Default;
 
+use crate::backup::{write_atomically, Backups};
+use crate::devices::{parse, DeviceVariations};
 use crate::error::ConvertError;
-use crate::xcstrings_docx_merger::config::Config;
-use crate::xcstrings_docx_merger::docx_extractor::extract;
+use crate::xcstrings_docx_merger::config::{Config, ConflictPolicy, PlaceholderPolicy};
+use crate::xcstrings_docx_merger::conflicts::{resolve_conflicts, Conflict};
+use crate::xcstrings_docx_merger::diff::{diff, string_unit, to_html, to_table, Change};
+use crate::xcstrings_docx_merger::docx_extractor::{extract, ExtractContainer};
+use crate::xcstrings_docx_merger::states::StateMapping;
+use crate::xcstrings_docx_merger::validation::{
+    validate_extracted, validate_plural_categories, PlaceholderProblem, PluralProblem,
+};
 use serde::Serialize;
 use serde_json_fmt::JsonFormat;
-use swift_localizable_json_parser::types::inoutoutput::StringUnitContainer;
-use swift_localizable_json_parser::types::input::TranslationTypeContainer;
-use swift_localizable_json_parser::types::output::PluralVariate;
-use swift_localizable_json_parser::{NEW_STATE, TRANSLATED_STATE};
-
-#[derive(Clone, Debug, Serialize)]
+use std::path::{Path, PathBuf};
+use swift_localizable_json_parser::types::inoutoutput::{
+    Plural, StringUnitContainer, TranslationValue,
+};
+use swift_localizable_json_parser::types::input::{Translation, TranslationTypeContainer};
+use swift_localizable_json_parser::types::output::{Parsed, PluralVariate};
+use swift_localizable_json_parser::NEW_STATE;
+
+#[derive(Clone, Debug, Default, Serialize)]
 pub struct Export {
     pub keys_translated: i32,
     pub keys_to_translate: i32,
+    /// Only filled when the changes are requested
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub changes: Option<Vec<Change>>,
+    /// Rows changed in the catalog after the document was written, see [resolve_conflicts]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub conflicts: Vec<Conflict>,
+    /// Translations with broken placeholders, see [validate_extracted]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub placeholder_problems: Vec<PlaceholderProblem>,
+    /// Plural keys with missing or unsupported categories, see [validate_plural_categories]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub plural_problems: Vec<PluralProblem>,
+}
+
+impl Export {
+    /// Sums the exports of multiple languages
+    pub(crate) fn add(&mut self, other: Export) {
+        self.keys_translated += other.keys_translated;
+        self.keys_to_translate += other.keys_to_translate;
+        self.conflicts.extend(other.conflicts);
+        self.placeholder_problems.extend(other.placeholder_problems);
+        self.plural_problems.extend(other.plural_problems);
+    }
 }
 
+/// Merges every language of the document in a single pass
 pub fn merge(config: Config) -> Result<Export, ConvertError> {
-    if config.base_xcstrings.exists() {
-        log::debug!(
-            "xcstrings file exists at path: {:#?}",
-            config.base_xcstrings
-        );
+    let state_mapping = StateMapping::new(
+        &config.import_state,
+        config.state_precedence,
+        &config.state_mapping,
+    )?;
+    let containers = extract(&config.extract_from_docx)?;
+    let (mut parsed, mut devices) = read_xcstrings_with_devices(&config.base_xcstrings)?;
+    let before = (config.dry_run || config.diff_report.is_some())
+        .then(|| (parsed.translation.clone(), devices.clone()));
+    let export = merge_containers(
+        &mut parsed,
+        &mut devices,
+        containers,
+        config.conflict_policy,
+        config.placeholder_policy,
+        &state_mapping,
+        before.as_ref(),
+    )?;
+
+    if let (Some(diff_report), Some(changes)) = (&config.diff_report, &export.changes) {
+        let report = if diff_report.extension().map(|e| e == "html") == Some(true) {
+            to_html(changes)
+        } else {
+            to_table(changes)
+        };
+
+        std::fs::write(diff_report, report).map_err(ConvertError::io(diff_report))?;
+    }
+
+    if config.dry_run {
+        log::debug!("Dry run, not writing to: {:#?}", config.updated_xcstrings);
     } else {
-        exit_with_log!(format!(
-            "xcstrings file does not exists at path: {:#?}",
-            config.base_xcstrings
+        write_xcstrings_with_devices(
+            &config.updated_xcstrings,
+            &parsed.translation,
+            &devices,
+            &config.backups,
+        )?;
+    }
+
+    Ok(export)
+}
+
+/// Resolves the conflicts, validates the placeholders and applies the extracted languages of a docx file to the catalog.
+/// The changes are only computed when the catalog before the merge is passed.
+pub(crate) fn merge_containers(
+    parsed: &mut Parsed,
+    devices: &mut DeviceVariations,
+    containers: Vec<ExtractContainer>,
+    conflict_policy: ConflictPolicy,
+    placeholder_policy: PlaceholderPolicy,
+    state_mapping: &StateMapping,
+    before: Option<&(Translation, DeviceVariations)>,
+) -> Result<Export, ConvertError> {
+    let mut export = Export {
+        changes: before.map(|_| vec![]),
+        ..Default::default()
+    };
+
+    for mut extracted in containers {
+        let conflicts = resolve_conflicts(parsed, devices, &mut extracted, conflict_policy);
+        let placeholder_problems =
+            validate_extracted(parsed, devices, &mut extracted, placeholder_policy);
+        let plural_problems = validate_plural_categories(&extracted);
+        let language_code = extracted.language_code.clone();
+        let merged = extracted
+            .extracted
+            .iter()
+            .map(|e| (e.key.clone(), e.device, e.variation.clone()))
+            .collect::<Vec<_>>();
+
+        export.add(apply_extracted(
+            &mut parsed.translation,
+            devices,
+            extracted,
+            state_mapping,
+        )?);
+        export.conflicts.extend(conflicts);
+        export.placeholder_problems.extend(placeholder_problems);
+        export.plural_problems.extend(plural_problems);
+
+        if let (Some(before), Some(changes)) = (before, &mut export.changes) {
+            changes.extend(diff(
+                (&before.0, &before.1),
+                (&parsed.translation, devices),
+                &language_code,
+                &merged,
+            ));
+        }
+    }
+
+    if placeholder_policy == PlaceholderPolicy::Fail && !export.placeholder_problems.is_empty() {
+        return Err(ConvertError::InvalidPlaceholders(
+            export.placeholder_problems,
         ));
     }
 
-    let mut parsed = swift_localizable_json_parser::parse_from_file(&config.base_xcstrings)?;
+    Ok(export)
+}
+
+/// Merges already extracted translations, regardless of the file format they came from
+pub(crate) fn merge_extracted(
+    base_xcstrings: &PathBuf,
+    updated_xcstrings: &PathBuf,
+    backups: &Backups,
+    containers: Vec<ExtractContainer>,
+) -> Result<Export, ConvertError> {
+    let mut parsed = read_xcstrings(base_xcstrings)?;
+    let mut export = Export::default();
+
+    for extracted in containers {
+        export.add(apply_extracted(
+            &mut parsed.translation,
+            &mut Default::default(),
+            extracted,
+            &StateMapping::default(),
+        )?);
+    }
+
+    log::debug!(
+        "Successfully updated Localized file with {} translated keys and {} keys to translate, trying to write it back to: {:#?}",
+        export.keys_translated,
+        export.keys_to_translate,
+        updated_xcstrings
+    );
+
+    write_xcstrings(updated_xcstrings, &parsed.translation, backups)?;
+
+    Ok(export)
+}
+
+pub(crate) fn read_xcstrings(base_xcstrings: &PathBuf) -> Result<Parsed, ConvertError> {
+    if base_xcstrings.exists() {
+        log::debug!("xcstrings file exists at path: {:#?}", base_xcstrings);
+    } else {
+        return Err(ConvertError::MissingFile {
+            path: base_xcstrings.clone(),
+        });
+    }
+
+    Ok(swift_localizable_json_parser::parse_from_file(
+        base_xcstrings,
+    )?)
+}
+
+/// [read_xcstrings] for catalogs that can have device variations, see [DeviceVariations]
+pub(crate) fn read_xcstrings_with_devices(
+    base_xcstrings: &PathBuf,
+) -> Result<(Parsed, DeviceVariations), ConvertError> {
+    if !base_xcstrings.exists() {
+        return Err(ConvertError::MissingFile {
+            path: base_xcstrings.clone(),
+        });
+    }
+
+    parse(&std::fs::read(base_xcstrings).map_err(ConvertError::io(base_xcstrings))?)
+}
+
+pub(crate) fn write_xcstrings(
+    updated_xcstrings: &Path,
+    translation: &Translation,
+    backups: &Backups,
+) -> Result<(), ConvertError> {
+    write_json(updated_xcstrings, translation, backups)
+}
+
+/// [write_xcstrings] with the keys with device variations put back
+pub(crate) fn write_xcstrings_with_devices(
+    updated_xcstrings: &Path,
+    translation: &Translation,
+    devices: &DeviceVariations,
+    backups: &Backups,
+) -> Result<(), ConvertError> {
+    if devices.is_empty() {
+        return write_xcstrings(updated_xcstrings, translation, backups);
+    }
+
+    let mut catalog = serde_json::to_value(translation)?;
+
+    devices.join(&mut catalog);
+
+    write_json(updated_xcstrings, &catalog, backups)
+}
+
+fn write_json<T: Serialize>(
+    updated_xcstrings: &Path,
+    catalog: &T,
+    backups: &Backups,
+) -> Result<(), ConvertError> {
+    // Apple for some reason adds a space before the colon, this is the reason we use another crate
+    let json = JsonFormat::pretty()
+        .colon(" : ")
+        .unwrap() // This is fine, should always work
+        .format_to_string(catalog)?; // This as well, but who knows...
+
+    write_atomically(updated_xcstrings, json, backups)
+}
+
+/// The keys with device variations are applied to the devices, every other key to the translation
+pub(crate) fn apply_extracted(
+    translation: &mut Translation,
+    devices: &mut DeviceVariations,
+    extracted: ExtractContainer,
+    state_mapping: &StateMapping,
+) -> Result<Export, ConvertError> {
     let mut keys_translated = 0;
     let mut keys_to_translate = 0;
-    let extracted = extract(&config.extract_from_docx)?;
 
     for extract in extracted.extracted {
-        let language = match parsed.translation.strings.get_mut(&extract.key) {
+        let translated = extract.translated.trim();
+        let state = if translated.is_empty() {
+            keys_to_translate += 1;
+
+            NEW_STATE.to_string()
+        } else {
+            keys_translated += 1;
+
+            let current = if extract.device.is_some() || devices.contains_key(&extract.key) {
+                devices.string_unit(
+                    &extracted.language_code,
+                    &extract.key,
+                    extract.device,
+                    extract.variation.as_ref(),
+                )
+            } else {
+                string_unit(
+                    translation,
+                    &extracted.language_code,
+                    &extract.key,
+                    extract.variation.as_ref(),
+                )
+            };
+
+            state_mapping.state_for(
+                &extract.key,
+                extract.state.as_deref(),
+                translated,
+                current.as_ref(),
+            )?
+        };
+
+        if extract.device.is_some() || devices.contains_key(&extract.key) {
+            devices.apply(
+                &extracted.language_code,
+                &extract.key,
+                extract.device,
+                extract.variation.as_ref(),
+                TranslationValue {
+                    state,
+                    value: translated.to_string(),
+                },
+            )?;
+
+            continue;
+        }
+
+        let language = match translation.strings.get_mut(&extract.key) {
             None => {
-                exit_with_log!(format!("There is no matching key for: {}", extract.key));
+                return Err(ConvertError::MissingKey { key: extract.key });
             }
             Some(v) => v,
         };
 
         macro_rules! update_string_unit_container {
             ($string_unit: expr) => {
-                let translated = extract.translated.trim();
-
-                $string_unit.string_unit.state = if translated.is_empty() {
-                    keys_to_translate += 1;
-
-                    NEW_STATE.to_string()
-                } else {
-                    keys_translated += 1;
-
-                    TRANSLATED_STATE.to_string()
-                };
+                $string_unit.string_unit.state = state.clone();
                 $string_unit.string_unit.value = translated.to_string();
             };
         }
@@ -73,7 +336,7 @@ pub fn merge(config: Config) -> Result<Export, ConvertError> {
         match container_to_work_on {
             TranslationTypeContainer::StringUnit(su) => {
                 if extract.variation.is_some() {
-                    exit_with_log!(format!("Expected no variation for key: {}", extract.key));
+                    return Err(ConvertError::UnexpectedVariation { key: extract.key });
                 }
 
                 update_string_unit_container!(su);
@@ -81,7 +344,7 @@ pub fn merge(config: Config) -> Result<Export, ConvertError> {
             TranslationTypeContainer::Variation(v) => {
                 let variation = match extract.variation {
                     None => {
-                        exit_with_log!(format!("Expected variation for key: {}", extract.key));
+                        return Err(ConvertError::MissingVariation { key: extract.key });
                     }
                     Some(v) => v,
                 };
@@ -89,39 +352,35 @@ pub fn merge(config: Config) -> Result<Export, ConvertError> {
 
                 update_string_unit_container!(container);
 
-                let field = match variation {
-                    PluralVariate::Zero => &mut v.variations.plural.zero,
-                    PluralVariate::One => &mut v.variations.plural.one,
-                    PluralVariate::Two => &mut v.variations.plural.two,
-                    PluralVariate::Few => &mut v.variations.plural.few,
-                    PluralVariate::Many => &mut v.variations.plural.many,
-                    PluralVariate::Other => &mut v.variations.plural.other,
-                };
-
-                *field = Some(container);
+                *plural_field(&mut v.variations.plural, &variation) = Some(container);
             }
         }
     }
 
-    log::debug!(
-        "Successfully updated Localized file with {keys_translated} translated keys and {keys_to_translate} keys to translate, trying to write it back to: {:#?}",
-        config.updated_xcstrings
-    );
-
-    // Apple for some reason adds a space before the colon, this is the reason we use another crate
-    let json = JsonFormat::pretty()
-        .colon(" : ")
-        .unwrap() // This is fine, should always work
-        .format_to_string(&parsed.translation)?; // This as well, but who knows...
-
-    std::fs::write(&config.updated_xcstrings, json)?;
-
     Ok(Export {
         keys_translated,
         keys_to_translate,
+        changes: None,
+        conflicts: vec![],
+        placeholder_problems: vec![],
+        plural_problems: vec![],
     })
 }
 
+pub(crate) fn plural_field<'a>(
+    plural: &'a mut Plural,
+    variate: &PluralVariate,
+) -> &'a mut Option<StringUnitContainer> {
+    match variate {
+        PluralVariate::Zero => &mut plural.zero,
+        PluralVariate::One => &mut plural.one,
+        PluralVariate::Two => &mut plural.two,
+        PluralVariate::Few => &mut plural.few,
+        PluralVariate::Many => &mut plural.many,
+        PluralVariate::Other => &mut plural.other,
+    }
+}
+
 pub fn extract_text_from_table_row_content(
     table_row_content: &TableRowContent,
 ) -> Result<String, ConvertError> {
@@ -170,7 +429,7 @@ pub fn extract_text_from_table_row_content(
 
 #[cfg(test)]
 mod test {
-    use crate::xcstrings_docx_merger::config::Config;
+    use crate::xcstrings_docx_merger::config::{Config, ConflictPolicy, PlaceholderPolicy};
     use crate::xcstrings_docx_merger::convert::merge;
     use std::env::current_dir;
 
@@ -190,6 +449,14 @@ mod test {
             extract_from_docx: nl,
             base_xcstrings: xcstrings.clone(),
             updated_xcstrings: xcstrings_updated.clone(),
+            backups: Default::default(),
+            dry_run: false,
+            diff_report: None,
+            conflict_policy: ConflictPolicy::Skip,
+            placeholder_policy: PlaceholderPolicy::Warn,
+            import_state: "translated".to_string(),
+            state_precedence: Default::default(),
+            state_mapping: vec![],
         })
         .unwrap();
 
@@ -197,6 +464,14 @@ mod test {
             extract_from_docx: pl,
             base_xcstrings: xcstrings_updated.clone(),
             updated_xcstrings: xcstrings_updated.clone(),
+            backups: Default::default(),
+            dry_run: false,
+            diff_report: None,
+            conflict_policy: ConflictPolicy::Skip,
+            placeholder_policy: PlaceholderPolicy::Warn,
+            import_state: "translated".to_string(),
+            state_precedence: Default::default(),
+            state_mapping: vec![],
         })
         .unwrap();
 
@@ -216,4 +491,81 @@ mod test {
             String::from_utf8(xcstrings_updated_bytes).unwrap()
         );
     }
+
+    #[test]
+    fn test_device_round_trip() {
+        let raw = include_bytes!("../../resources/device_test_base.xcstrings");
+        let generated = current_dir().unwrap().join("generated").join("devices");
+
+        crate::docx_writer::convert::convert_from_raw(
+            raw,
+            crate::docx_writer::config::Config {
+                save_in: generated.clone(),
+                clean_dir_before_generating: true,
+                new_language_codes: vec![],
+                columns_in_output: vec![],
+                path_to_xcstrings: Default::default(),
+                translation_memories: vec![],
+                suggestion_catalogs: vec![],
+                suggestion_threshold: 70,
+                language_groups: vec![],
+                filter: Default::default(),
+            },
+        )
+        .unwrap();
+
+        let mut containers =
+            crate::xcstrings_docx_merger::docx_extractor::extract(&generated.join("nl.docx"))
+                .unwrap();
+
+        for extract in &mut containers[0].extracted {
+            if extract.key == "tap" && extract.translated.is_empty() {
+                extract.translated = format!("nl {}", extract.unit_id());
+            }
+        }
+
+        let (mut parsed, mut devices) = crate::devices::parse(raw).unwrap();
+
+        super::merge_containers(
+            &mut parsed,
+            &mut devices,
+            containers,
+            ConflictPolicy::Skip,
+            PlaceholderPolicy::Warn,
+            &Default::default(),
+            None,
+        )
+        .unwrap();
+
+        let updated = generated.join("updated.xcstrings");
+
+        super::write_xcstrings_with_devices(
+            &updated,
+            &parsed.translation,
+            &devices,
+            &Default::default(),
+        )
+        .unwrap();
+
+        let updated: serde_json::Value =
+            serde_json::from_slice(&std::fs::read(&updated).unwrap()).unwrap();
+        let nl = &updated["strings"]["tap"]["localizations"]["nl"]["variations"]["device"];
+
+        // The translated mac branch is kept, the other devices are added with their plurals
+        assert_eq!("Klik", nl["mac"]["stringUnit"]["value"]);
+        assert_eq!(
+            "nl tap|==|device.iphone",
+            nl["iphone"]["stringUnit"]["value"]
+        );
+        assert_eq!(
+            "nl tap|==|device.other.one",
+            nl["other"]["variations"]["plural"]["one"]["stringUnit"]["value"]
+        );
+        assert_eq!(
+            updated["strings"]["tap"]["localizations"]["en"],
+            serde_json::from_slice::<serde_json::Value>(raw).unwrap()["strings"]["tap"]
+                ["localizations"]["en"]
+        );
+        assert!(updated["strings"]["plain"].is_object());
+    }
 }
@@ -0,0 +1,322 @@
+use crate::devices::{device_variation_text, DeviceVariate, DeviceVariations};
+use crate::xml::escape;
+use serde::Serialize;
+use swift_localizable_json_parser::types::inoutoutput::StringUnitContainer;
+use swift_localizable_json_parser::types::input::{Translation, TranslationTypeContainer};
+use swift_localizable_json_parser::types::output::PluralVariate;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ChangeKind {
+    Addition,
+    Modification,
+    Clearing,
+    Unchanged,
+}
+
+impl ChangeKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ChangeKind::Addition => "addition",
+            ChangeKind::Modification => "modification",
+            ChangeKind::Clearing => "clearing",
+            ChangeKind::Unchanged => "unchanged",
+        }
+    }
+}
+
+/// What a merge does with a single key and plural variate
+#[derive(Clone, Debug, Serialize)]
+pub struct Change {
+    pub language_code: String,
+    pub key: String,
+    pub variation: Option<String>,
+    pub old_value: Option<String>,
+    pub old_state: Option<String>,
+    pub new_value: Option<String>,
+    pub new_state: Option<String>,
+    pub kind: ChangeKind,
+}
+
+const HEADER: [&str; 7] = [
+    "Language",
+    "Key",
+    "Variation",
+    "Change",
+    "Old",
+    "New",
+    "State",
+];
+
+/// Compares the catalog before and after the merge, only for the merged keys and variates
+pub(crate) fn diff(
+    before: (&Translation, &DeviceVariations),
+    after: (&Translation, &DeviceVariations),
+    language_code: &str,
+    merged: &[(String, Option<DeviceVariate>, Option<PluralVariate>)],
+) -> Vec<Change> {
+    // Keys with device variations are not in the parsed catalog
+    let unit = |(translation, devices): (&Translation, &DeviceVariations),
+                key: &str,
+                device: Option<DeviceVariate>,
+                variation: Option<&PluralVariate>| {
+        if devices.contains_key(key) {
+            devices.string_unit(language_code, key, device, variation)
+        } else {
+            string_unit(translation, language_code, key, variation)
+        }
+    };
+
+    merged
+        .iter()
+        .map(|(key, device, variation)| {
+            let old = unit(before, key, *device, variation.as_ref());
+            let new = unit(after, key, *device, variation.as_ref());
+            let value =
+                |c: &Option<StringUnitContainer>| c.as_ref().map(|c| c.string_unit.value.clone());
+            let state =
+                |c: &Option<StringUnitContainer>| c.as_ref().map(|c| c.string_unit.state.clone());
+            let (old_value, new_value) = (value(&old), value(&new));
+            let (old_state, new_state) = (state(&old), state(&new));
+            let old_text = old_value.as_deref().unwrap_or_default();
+            let new_text = new_value.as_deref().unwrap_or_default();
+            let kind = if old_text == new_text && (old_text.is_empty() || old_state == new_state) {
+                ChangeKind::Unchanged
+            } else if old_text.is_empty() {
+                ChangeKind::Addition
+            } else if new_text.is_empty() {
+                ChangeKind::Clearing
+            } else {
+                ChangeKind::Modification
+            };
+
+            Change {
+                language_code: language_code.to_string(),
+                key: key.clone(),
+                variation: match device {
+                    Some(device) => Some(device_variation_text(*device, variation.as_ref())),
+                    None => variation.as_ref().map(|v| v.android_key().to_string()),
+                },
+                old_value,
+                old_state,
+                new_value,
+                new_state,
+                kind,
+            }
+        })
+        .collect()
+}
+
+pub(crate) fn string_unit(
+    translation: &Translation,
+    language_code: &str,
+    key: &str,
+    variation: Option<&PluralVariate>,
+) -> Option<StringUnitContainer> {
+    let container = translation
+        .strings
+        .get(key)?
+        .localizations
+        .get(language_code)?;
+
+    match (container, variation) {
+        (TranslationTypeContainer::StringUnit(su), None) => Some(su.clone()),
+        (TranslationTypeContainer::Variation(v), Some(variation)) => {
+            let plural = &v.variations.plural;
+
+            match variation {
+                PluralVariate::Zero => plural.zero.clone(),
+                PluralVariate::One => plural.one.clone(),
+                PluralVariate::Two => plural.two.clone(),
+                PluralVariate::Few => plural.few.clone(),
+                PluralVariate::Many => plural.many.clone(),
+                PluralVariate::Other => plural.other.clone(),
+            }
+        }
+        _ => None,
+    }
+}
+
+fn cells(change: &Change) -> [String; 7] {
+    let state = match (&change.old_state, &change.new_state) {
+        (Some(old), Some(new)) if old != new => format!("{old} -> {new}"),
+        (_, Some(new)) => new.clone(),
+        (Some(old), None) => old.clone(),
+        (None, None) => String::new(),
+    };
+
+    [
+        change.language_code.clone(),
+        change.key.clone(),
+        change.variation.clone().unwrap_or_default(),
+        change.kind.as_str().to_string(),
+        change.old_value.clone().unwrap_or_default(),
+        change.new_value.clone().unwrap_or_default(),
+        state,
+    ]
+}
+
+/// A plain text table for reviewers, newlines in values are shown as \n
+pub fn to_table(changes: &[Change]) -> String {
+    let rows = std::iter::once(HEADER.map(|h| h.to_string()))
+        .chain(changes.iter().map(cells))
+        .map(|row| row.map(|cell| cell.replace('\n', "\\n")))
+        .collect::<Vec<_>>();
+    let widths = (0..HEADER.len())
+        .map(|index| {
+            rows.iter()
+                .map(|row| row[index].chars().count())
+                .max()
+                .unwrap_or_default()
+        })
+        .collect::<Vec<_>>();
+
+    rows.iter()
+        .map(|row| {
+            row.iter()
+                .zip(&widths)
+                .map(|(cell, width)| format!("{cell:width$}"))
+                .collect::<Vec<_>>()
+                .join(" | ")
+                .trim_end()
+                .to_string()
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+pub fn to_html(changes: &[Change]) -> String {
+    let mut html = vec![
+        "<!DOCTYPE html>".to_string(),
+        "<html>".to_string(),
+        "<head><meta charset=\"utf-8\"><style>td { white-space: pre-wrap; vertical-align: top; } .addition { background: #e6ffec; } .modification { background: #fff8c5; } .clearing { background: #ffebe9; }</style></head>".to_string(),
+        "<body>".to_string(),
+        "<table>".to_string(),
+        format!(
+            "<tr>{}</tr>",
+            HEADER.map(|h| format!("<th>{h}</th>")).join("")
+        ),
+    ];
+
+    for change in changes {
+        let cells = cells(change);
+
+        html.push(format!(
+            "<tr class=\"{}\">{}</tr>",
+            cells[3],
+            cells
+                .iter()
+                .map(|c| format!("<td>{}</td>", escape(c)))
+                .collect::<String>()
+        ));
+    }
+
+    html.push("</table>".to_string());
+    html.push("</body>".to_string());
+    html.push("</html>".to_string());
+
+    html.join("\n")
+}
+
+#[cfg(test)]
+mod test {
+    use crate::devices::DeviceVariate;
+    use crate::xcstrings_docx_merger::config::{Config, ConflictPolicy, PlaceholderPolicy};
+    use crate::xcstrings_docx_merger::convert::{
+        merge, merge_containers, read_xcstrings_with_devices,
+    };
+    use crate::xcstrings_docx_merger::diff::ChangeKind;
+    use crate::xcstrings_docx_merger::docx_extractor::{Extract, ExtractContainer};
+    use std::env::current_dir;
+
+    #[test]
+    fn test_dry_run() {
+        let base = current_dir().unwrap();
+        let resources = base.join("resources");
+        let save_in = base.join("generated").join("diff");
+        let updated_xcstrings = save_in.join("updated.xcstrings");
+        let diff_report = save_in.join("diff.html");
+
+        let _ = std::fs::remove_dir_all(&save_in);
+        std::fs::create_dir_all(&save_in).unwrap();
+
+        let export = merge(Config {
+            extract_from_docx: resources.join("reader_test_updated_nl.docx"),
+            base_xcstrings: resources.join("reader_test_base.xcstrings"),
+            updated_xcstrings: updated_xcstrings.clone(),
+            backups: Default::default(),
+            dry_run: true,
+            diff_report: Some(diff_report.clone()),
+            conflict_policy: ConflictPolicy::Skip,
+            placeholder_policy: PlaceholderPolicy::Warn,
+            import_state: "translated".to_string(),
+            state_precedence: Default::default(),
+            state_mapping: vec![],
+        })
+        .unwrap();
+        let changes = export.changes.unwrap();
+
+        let kind_of = |key: &str, variation: Option<&str>| {
+            changes
+                .iter()
+                .find(|c| c.key == key && c.variation.as_deref() == variation)
+                .unwrap()
+                .kind
+        };
+
+        assert_eq!(ChangeKind::Modification, kind_of("normal_0", None));
+        assert_eq!(ChangeKind::Addition, kind_of("plurals", Some("other")));
+        assert_eq!(ChangeKind::Unchanged, kind_of("plurals", Some("one")));
+        assert!(!updated_xcstrings.exists());
+        assert!(std::fs::read_to_string(diff_report)
+            .unwrap()
+            .contains("<tr class=\"addition\">"));
+    }
+
+    #[test]
+    fn test_diff_devices() {
+        let (mut parsed, mut devices) = read_xcstrings_with_devices(
+            &current_dir()
+                .unwrap()
+                .join("resources")
+                .join("device_test_base.xcstrings"),
+        )
+        .unwrap();
+        let before = (parsed.translation.clone(), devices.clone());
+        let extract = |device, translated: &str| Extract {
+            key: "tap".to_string(),
+            variation: None,
+            device: Some(device),
+            translated: translated.to_string(),
+            state: None,
+        };
+        let export = merge_containers(
+            &mut parsed,
+            &mut devices,
+            vec![ExtractContainer {
+                language_code: "nl".to_string(),
+                extracted: vec![
+                    extract(DeviceVariate::IPhone, "Tik"),
+                    extract(DeviceVariate::Mac, "Klikken"),
+                ],
+                fingerprints: None,
+            }],
+            ConflictPolicy::Skip,
+            PlaceholderPolicy::Warn,
+            &Default::default(),
+            Some(&before),
+        )
+        .unwrap();
+        let changes = export.changes.unwrap();
+        let change_of = |variation: &str| {
+            changes
+                .iter()
+                .find(|c| c.key == "tap" && c.variation.as_deref() == Some(variation))
+                .unwrap()
+        };
+
+        assert_eq!(ChangeKind::Addition, change_of("device.iphone").kind);
+        assert_eq!(ChangeKind::Modification, change_of("device.mac").kind);
+        assert_eq!(Some("Klik"), change_of("device.mac").old_value.as_deref());
+    }
+}
@@ -1,27 +1,60 @@
-use crate::{KEY_KEY, KEY_VARIATION};
-use docx_rust::document::BodyContent;
+use crate::{KEY_KEY, KEY_STATE, KEY_VARIATION};
+use docx_rust::document::{BodyContent, TableRow};
+use std::collections::HashMap;
 use std::path::PathBuf;
 
+use crate::devices::{
+    device_unit_id, device_variation_text, split_device_variation, DeviceVariate,
+};
 use crate::error::ConvertError;
+use crate::fingerprint::{from_description, Fingerprints};
 use crate::xcstrings_docx_merger::convert::extract_text_from_table_row_content;
+use crate::xliff_writer::convert::unit_id;
 use swift_localizable_json_parser::types::output::PluralVariate;
 
 pub struct ExtractContainer {
     pub(crate) language_code: String,
     pub(crate) extracted: Vec<Extract>,
+    /// The fingerprints of the rows when the document was written, only docx files have them
+    pub(crate) fingerprints: Option<Fingerprints>,
 }
 
 pub(crate) struct Extract {
     pub(crate) key: String,
     pub(crate) variation: Option<PluralVariate>,
+    /// Only set for keys with device variations, see [crate::devices::DeviceVariations]
+    pub(crate) device: Option<DeviceVariate>,
     pub(crate) translated: String,
+    /// The state of the State column, see [crate::xcstrings_docx_merger::states::StateMapping]
+    pub(crate) state: Option<String>,
 }
 
-pub fn extract(extract_from_docx: &PathBuf) -> Result<ExtractContainer, ConvertError> {
+impl Extract {
+    /// See [crate::rows::Row::unit_id]
+    pub(crate) fn unit_id(&self) -> String {
+        match self.device {
+            None => unit_id(&self.key, self.variation.as_ref()),
+            Some(device) => device_unit_id(&self.key, device, self.variation.as_ref()),
+        }
+    }
+
+    /// The variation as reported to the user, see [crate::rows::Row::variation_text]
+    pub(crate) fn variation_text(&self) -> Option<String> {
+        match self.device {
+            Some(device) => Some(device_variation_text(device, self.variation.as_ref())),
+            None => self.variation.as_ref().map(|v| v.android_key().to_string()),
+        }
+    }
+}
+
+/// Extracts every language column of the document
+pub fn extract(extract_from_docx: &PathBuf) -> Result<Vec<ExtractContainer>, ConvertError> {
     if extract_from_docx.exists() {
         log::debug!("docx file exists...");
     } else {
-        exit_with_log!("docx file does not exists");
+        return Err(ConvertError::MissingFile {
+            path: extract_from_docx.clone(),
+        });
     }
 
     let docxfile = match docx_rust::DocxFile::from_file(extract_from_docx) {
@@ -30,9 +63,7 @@ pub fn extract(extract_from_docx: &PathBuf) -> Result<ExtractContainer, ConvertE
 
             ok
         }
-        Err(err) => {
-            exit_with_log!(format!("Got error while reading docx file: {:#?}", err));
-        }
+        Err(err) => return Err(ConvertError::from(err)),
     };
     let parsed = match docxfile.parse() {
         Ok(ok) => {
@@ -40,9 +71,7 @@ pub fn extract(extract_from_docx: &PathBuf) -> Result<ExtractContainer, ConvertE
 
             ok
         }
-        Err(err) => {
-            exit_with_log!(format!("Got error while parsing docx file: {:#?}", err));
-        }
+        Err(err) => return Err(ConvertError::from(err)),
     };
 
     let tables = parsed
@@ -56,96 +85,395 @@ pub fn extract(extract_from_docx: &PathBuf) -> Result<ExtractContainer, ConvertE
         })
         .collect::<Vec<_>>();
 
-    assert_eq!(1, tables.len());
+    let cells = |table_row: &TableRow| {
+        table_row
+            .cells
+            .iter()
+            .map(extract_text_from_table_row_content)
+            .collect::<Result<Vec<_>, _>>()
+    };
+    let mut rows_per_table = vec![];
 
-    let table = tables[0];
+    for table in tables {
+        let mut table_rows = table.rows.iter();
+
+        // Tables added by the translator can contain anything, only the cells of translation tables are read
+        let header = match table_rows.next().map(cells) {
+            Some(Ok(header)) if is_translation_header(&header) => header,
+            _ => {
+                log::debug!("Skipping table without a translation header");
+
+                continue;
+            }
+        };
+        let mut rows = vec![header];
+
+        for table_row in table_rows {
+            rows.push(cells(table_row)?);
+        }
+
+        rows_per_table.push(rows);
+    }
+
+    let mut fingerprints = parsed
+        .core
+        .and_then(|core| core.description)
+        .and_then(|description| from_description(&description))
+        .unwrap_or_default();
+    // The writer stores the fingerprints per language it wrote a column for
+    let languages = fingerprints.keys().cloned().collect::<Vec<_>>();
+    let mut containers = extract_from_tables(rows_per_table, &languages)?;
+
+    for container in &mut containers {
+        container.fingerprints = fingerprints.remove(&container.language_code);
+
+        // A grouped document has rows of a single language, e.g. the few rows of pl in a nl and pl document
+        if let Some(written) = &container.fingerprints {
+            container
+                .extracted
+                .retain(|extract| written.0.contains_key(&extract.unit_id()));
+        }
+    }
+
+    Ok(containers)
+}
+
+/// Translators can add tables (notes, a cover page, ...), only tables with a translation header are extracted.
+/// A document can have multiple translation tables, e.g. 1 per section.
+/// See [extract_from_rows] for the languages.
+pub(crate) fn extract_from_tables(
+    rows_per_table: Vec<Vec<Vec<String>>>,
+    languages: &[String],
+) -> Result<Vec<ExtractContainer>, ConvertError> {
+    let mut containers: Vec<ExtractContainer> = vec![];
+
+    for rows in rows_per_table {
+        if !rows
+            .first()
+            .is_some_and(|header| is_translation_header(header))
+        {
+            log::debug!("Skipping table without a translation header");
+
+            continue;
+        }
+
+        for extracted in extract_from_rows(rows, languages)? {
+            match containers
+                .iter_mut()
+                .find(|c| c.language_code == extracted.language_code)
+            {
+                None => containers.push(extracted),
+                Some(container) => container.extracted.extend(extracted.extracted),
+            }
+        }
+    }
+
+    if containers.is_empty() {
+        return Err(ConvertError::MissingTranslationTable);
+    }
+
+    Ok(containers)
+}
+
+fn is_translation_header(header: &[String]) -> bool {
+    header.iter().any(|text| text.as_str() == KEY_KEY)
+        && header.iter().any(|text| text.as_str() == KEY_VARIATION)
+}
+
+/// The language of a column written by [per_language_header], e.g.: State (nl)
+fn language_of_header<'a>(text: &'a str, header: &str) -> Option<&'a str> {
+    text.strip_prefix(header)?
+        .strip_prefix(" (")?
+        .strip_suffix(')')
+}
+
+/// Extracts the translations from the cell texts of a table, the first row must be the header row.
+/// This is shared by every table like format (docx, xlsx, ...).
+/// The columns of the languages the document was written for are merged. Without languages (e.g. for xlsx or an older docx)
+/// the last column is the language, the writers always write it as the last column.
+/// Other columns are never merged: the base language, suggestions, a Tip column of the translator, ...
+pub(crate) fn extract_from_rows(
+    rows: Vec<Vec<String>>,
+    languages: &[String],
+) -> Result<Vec<ExtractContainer>, ConvertError> {
+    let header = match rows.first() {
+        None => {
+            exit_with_log!("There is no header row");
+        }
+        Some(header) => header,
+    };
     let mut index_key = None;
     let mut index_variation = None;
-    let mut language_code = None;
-    let index_translated = table.rows[0].cells.len() - 1;
+    let mut index_state = None;
+    let mut index_state_per_language = HashMap::new();
+    let mut language_columns = vec![];
 
-    for (index, header) in table.rows[0].cells.iter().enumerate() {
-        let text = extract_text_from_table_row_content(header)?;
+    for (index, text) in header.iter().enumerate() {
+        let text = text.as_str();
 
         // For some reason, matching does not work
-        if text.as_str() == KEY_KEY {
+        if text == KEY_KEY {
             index_key = Some(index);
-        } else if text.as_str() == KEY_VARIATION {
+        } else if text == KEY_VARIATION {
             index_variation = Some(index);
+        } else if text == KEY_STATE {
+            index_state = Some(index);
+        } else if let Some(language) = language_of_header(text, KEY_STATE) {
+            index_state_per_language.insert(language.to_string(), index);
+        } else if languages.iter().any(|language| language == text) {
+            language_columns.push((index, text.to_string()));
         }
+    }
 
-        if index == index_translated {
-            language_code = Some(text.to_string());
+    // Documents without languages have a single language, in the last column
+    if languages.is_empty() {
+        if let Some((index, text)) = header.iter().enumerate().next_back() {
+            if !text.is_empty() && index_key != Some(index) && index_variation != Some(index) {
+                language_columns.push((index, text.clone()));
+            }
         }
     }
 
     let index_key = match index_key {
         None => {
-            exit_with_log!("There is no key column");
+            return Err(ConvertError::MissingColumn(KEY_KEY.to_string()));
         }
         Some(index) => index,
     };
     let index_variation = match index_variation {
         None => {
-            exit_with_log!("There is no variation column");
+            return Err(ConvertError::MissingColumn(KEY_VARIATION.to_string()));
         }
         Some(index) => index,
     };
-    let language_code = match language_code {
-        None => {
-            exit_with_log!(
-                "There is no language code to translate from, this should be the last column"
-            );
-        }
-        Some(language_code) => language_code,
-    };
 
-    let mut extracted = vec![];
+    if language_columns.is_empty() {
+        exit_with_log!("There is no language code to translate to, this should be the header of the last column");
+    }
+
+    let mut containers = language_columns
+        .iter()
+        .map(|(_, language_code)| ExtractContainer {
+            language_code: language_code.clone(),
+            extracted: vec![],
+            fingerprints: None,
+        })
+        .collect::<Vec<_>>();
 
-    for table_row in table.rows.iter().skip(1) {
-        let key = extract_text_from_table_row_content(&table_row.cells[index_key])?;
-        let variation_raw = extract_text_from_table_row_content(&table_row.cells[index_variation])?;
-        let translated = extract_text_from_table_row_content(&table_row.cells[index_translated])?;
+    for row in rows.iter().skip(1) {
+        let cell = |index: usize| row.get(index).cloned().unwrap_or_default();
+        let key = cell(index_key);
+        let variation_raw = cell(index_variation);
 
         if key.is_empty() {
             return Err(ConvertError::from(format!(
-                "Found empty key, variation: {:#?}, translated value: {:#?}",
-                variation_raw, translated
+                "Found empty key, variation: {:#?}",
+                variation_raw
             )));
         }
 
         if variation_raw.is_empty() {
             return Err(ConvertError::from(format!(
-                "Found empty variation, key: {:#?}, translated value: {:#?}",
-                key, translated
+                "Found empty variation, key: {:#?}",
+                key
             )));
         }
 
-        extracted.push(Extract {
-            key,
-            variation: PluralVariate::from_android_key(&variation_raw),
-            translated,
-        });
+        let (device, variation) = match split_device_variation(&variation_raw) {
+            None => (None, PluralVariate::from_android_key(&variation_raw)),
+            Some((device, variation)) => (Some(device), variation),
+        };
+
+        for ((index_translated, language_code), container) in
+            language_columns.iter().zip(&mut containers)
+        {
+            let state = index_state_per_language
+                .get(language_code)
+                .or(index_state.as_ref())
+                .map(|index| cell(*index))
+                .filter(|state| !state.is_empty());
+
+            container.extracted.push(Extract {
+                key: key.clone(),
+                variation: variation.clone(),
+                device,
+                translated: cell(*index_translated),
+                state,
+            });
+        }
     }
 
-    Ok(ExtractContainer {
-        language_code,
-        extracted,
-    })
+    Ok(containers)
 }
 
 #[cfg(test)]
 mod test {
-    use crate::xcstrings_docx_merger::docx_extractor::extract;
+    use crate::xcstrings_docx_merger::docx_extractor::{extract, extract_from_tables};
+    use docx_rust::document::{
+        BodyContent, Paragraph, Table, TableCell, TableRow, TableRowContent,
+    };
+    use swift_localizable_json_parser::types::output::PluralVariate;
 
     #[test]
     fn test_extract() {
-        extract(
-            &std::env::current_dir()
+        let generated = std::env::current_dir().unwrap().join("generated");
+        let nl = generated.join("nl.docx");
+
+        extract(&nl).unwrap();
+
+        // A table with content that can't be read, e.g. a content control, is not a translation table
+        let with_notes = generated.join("nl_with_notes.docx");
+        let docx_file = docx_rust::DocxFile::from_file(&nl).unwrap();
+        let mut docx = docx_file.parse().unwrap();
+        let notes = Table::default()
+            .push_row(TableRow::default().push_cell(TableCell::paragraph(
+                Paragraph::default().push_text("Notes"),
+            )))
+            .push_row(TableRow::default().push_cell(TableRowContent::SDT(Default::default())));
+
+        docx.document
+            .body
+            .content
+            .insert(0, BodyContent::Table(notes));
+        docx.write_file(&with_notes).unwrap();
+
+        let extracted = extract(&with_notes).unwrap();
+
+        assert_eq!("nl", extracted[0].language_code);
+        assert!(!extracted[0].extracted.is_empty());
+    }
+
+    #[test]
+    fn test_extract_grouped() {
+        let base = std::env::current_dir().unwrap();
+        let save_in = base.join("generated").join("grouped_merge");
+        let updated = save_in.join("updated.xcstrings");
+
+        crate::docx_writer::convert::convert_from_path(crate::docx_writer::config::Config {
+            save_in: save_in.clone(),
+            clean_dir_before_generating: true,
+            new_language_codes: vec!["pl".to_string()],
+            path_to_xcstrings: base.join("resources").join("reader_test_base.xcstrings"),
+            columns_in_output: vec![],
+            translation_memories: vec![],
+            suggestion_catalogs: vec![],
+            suggestion_threshold: 70,
+            language_groups: vec!["nl,pl".to_string()],
+            filter: Default::default(),
+        })
+        .unwrap();
+
+        // The few and many rows of pl are not rows of nl
+        let extracted = extract(&save_in.join("nl_pl.docx")).unwrap();
+
+        assert!(!extracted[0].extracted.iter().any(|e| e.key == "plurals"
+            && matches!(e.variation, Some(PluralVariate::Few | PluralVariate::Many))));
+        assert!(extracted[1]
+            .extracted
+            .iter()
+            .any(|e| e.variation == Some(PluralVariate::Few)));
+
+        crate::xcstrings_docx_merger::convert::merge(
+            crate::xcstrings_docx_merger::config::Config {
+                extract_from_docx: save_in.join("nl_pl.docx"),
+                base_xcstrings: base.join("resources").join("reader_test_base.xcstrings"),
+                updated_xcstrings: updated.clone(),
+                backups: Default::default(),
+                dry_run: false,
+                diff_report: None,
+                conflict_policy: crate::xcstrings_docx_merger::config::ConflictPolicy::Skip,
+                placeholder_policy: crate::xcstrings_docx_merger::config::PlaceholderPolicy::Warn,
+                import_state: "translated".to_string(),
+                state_precedence: Default::default(),
+                state_mapping: vec![],
+            },
+        )
+        .unwrap();
+
+        let merged: serde_json::Value =
+            serde_json::from_slice(&std::fs::read(&updated).unwrap()).unwrap();
+        let plural = &merged["strings"]["plurals"]["localizations"]["nl"]["variations"]["plural"];
+
+        assert!(plural.get("one").is_some());
+        assert!(plural.get("few").is_none());
+        assert!(plural.get("many").is_none());
+    }
+
+    #[test]
+    fn test_extract_from_tables() {
+        let table = |rows: &[&[&str]]| {
+            rows.iter()
+                .map(|row| row.iter().map(|cell| cell.to_string()).collect())
+                .collect::<Vec<Vec<String>>>()
+        };
+        let extracted = extract_from_tables(
+            vec![
+                table(&[&["Notes"], &["Use informal language"]]),
+                table(&[&["Key", "Variation", "en", "nl"], &["a", "N/A", "A", "Een"]]),
+                table(&[
+                    &["Key", "Variation", "en", "nl"],
+                    &["b", "N/A", "B", "Twee"],
+                ]),
+            ],
+            &[],
+        )
+        .unwrap()
+        .remove(0);
+
+        assert_eq!("nl", extracted.language_code);
+        assert_eq!(2, extracted.extracted.len());
+        assert_eq!("Twee", extracted.extracted[1].translated);
+        assert_eq!(
+            "missing_translation_table",
+            extract_from_tables(vec![table(&[&["Notes"]])], &[])
+                .err()
                 .unwrap()
-                .join("generated")
-                .join("nl.docx"),
+                .code()
+        );
+
+        // Only the columns of the written languages are merged, the per language state wins over the State column
+        let extracted = extract_from_tables(
+            vec![table(&[
+                &[
+                    "Key",
+                    "Variation",
+                    "State",
+                    "State (pl)",
+                    "en",
+                    "Suggestion (nl)",
+                    "nl",
+                    "pl",
+                    "Tip",
+                ],
+                &[
+                    "a",
+                    "N/A",
+                    "new",
+                    "needs_review",
+                    "A",
+                    "",
+                    "Een",
+                    "Jeden",
+                    "Informal",
+                ],
+            ])],
+            &["nl".to_string(), "pl".to_string()],
         )
         .unwrap();
+
+        assert_eq!(
+            vec!["nl", "pl"],
+            extracted
+                .iter()
+                .map(|e| e.language_code.as_str())
+                .collect::<Vec<_>>()
+        );
+        assert_eq!("Een", extracted[0].extracted[0].translated);
+        assert_eq!(Some("new"), extracted[0].extracted[0].state.as_deref());
+        assert_eq!("Jeden", extracted[1].extracted[0].translated);
+        assert_eq!(
+            Some("needs_review"),
+            extracted[1].extracted[0].state.as_deref()
+        );
     }
 }
@@ -0,0 +1,239 @@
+use crate::error::ConvertError;
+use crate::xcstrings_docx_merger::config::StatePrecedence;
+use crate::{NEEDS_REVIEW_STATE, PREFILLED_STATE, STALE_STATE};
+use std::collections::HashMap;
+use swift_localizable_json_parser::types::inoutoutput::StringUnitContainer;
+use swift_localizable_json_parser::{NEW_STATE, TRANSLATED_STATE};
+
+const STATES: [&str; 4] = [NEW_STATE, TRANSLATED_STATE, NEEDS_REVIEW_STATE, STALE_STATE];
+
+/// Decides the state of merged translations
+#[derive(Clone, Debug)]
+pub struct StateMapping {
+    /// The state of changed translations without a state of their own
+    import_state: String,
+    precedence: StatePrecedence,
+    /// Renames the states of the State column
+    mapping: HashMap<String, String>,
+}
+
+impl Default for StateMapping {
+    fn default() -> Self {
+        Self {
+            import_state: TRANSLATED_STATE.to_string(),
+            precedence: StatePrecedence::default(),
+            mapping: HashMap::new(),
+        }
+    }
+}
+
+impl StateMapping {
+    /// The mapping is formatted as from=to, e.g. approved=translated
+    pub fn new(
+        import_state: &str,
+        precedence: StatePrecedence,
+        mapping: &[String],
+    ) -> Result<Self, ConvertError> {
+        let mapping = mapping
+            .iter()
+            .map(|m| match m.split_once('=') {
+                None => Err(ConvertError::from(format!(
+                    "Expected a state mapping as from=to, got: {m}"
+                ))),
+                Some((from, to)) => Ok((normalize(from), to.trim().to_string())),
+            })
+            .collect::<Result<_, _>>()?;
+
+        Ok(Self {
+            import_state: validate(import_state)?.to_string(),
+            precedence,
+            mapping,
+        })
+    }
+
+    /// The state of a non-empty translation.
+    /// An unchanged translation keeps the state of the catalog, unless the row has a state, e.g.: a reviewer approving it.
+    /// A changed translation gets the state of the row or the import state, depending on the precedence.
+    pub fn state_for(
+        &self,
+        key: &str,
+        row_state: Option<&str>,
+        translated: &str,
+        current: Option<&StringUnitContainer>,
+    ) -> Result<String, ConvertError> {
+        let row_state = self.row_state(key, row_state)?;
+        let current = current.map(|c| &c.string_unit);
+
+        if let Some(current) = current.filter(|c| c.value == translated) {
+            return Ok(row_state.unwrap_or_else(|| current.state.clone()));
+        }
+
+        match (self.precedence, row_state) {
+            (StatePrecedence::RowState, Some(row_state)) => Ok(row_state),
+            _ => Ok(self.import_state.clone()),
+        }
+    }
+
+    /// The mapped state of the row, None if the row doesn't have one.
+    /// A translator filling in a new row doesn't set a state, so these rows don't have one either.
+    /// Rows still marked as prefilled were never reviewed and need a review, unless the mapping says otherwise, e.g.: prefilled=translated
+    fn row_state(
+        &self,
+        key: &str,
+        row_state: Option<&str>,
+    ) -> Result<Option<String>, ConvertError> {
+        let row_state = row_state.map(normalize);
+        let row_state = row_state
+            .as_deref()
+            .map(|s| self.mapping.get(s).map(|m| m.as_str()).unwrap_or(s));
+
+        match row_state {
+            None | Some("") | Some(NEW_STATE) => Ok(None),
+            Some(PREFILLED_STATE) => Ok(Some(NEEDS_REVIEW_STATE.to_string())),
+            Some(state) => match validate(state) {
+                Ok(state) => Ok(Some(state.to_string())),
+                Err(_) => Err(ConvertError::from(format!(
+                    "Unknown state {state} for key: {key}"
+                ))),
+            },
+        }
+    }
+}
+
+/// Word capitalizes the first letter of a cell by default, e.g.: New or Needs review
+fn normalize(state: &str) -> String {
+    state.trim().to_lowercase().replace(' ', "_")
+}
+
+fn validate(state: &str) -> Result<&str, ConvertError> {
+    if STATES.contains(&state) {
+        Ok(state)
+    } else {
+        Err(ConvertError::from(format!(
+            "Unknown state: {state}, expected one of: {}",
+            STATES.join(", ")
+        )))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::xcstrings_docx_merger::config::StatePrecedence;
+    use crate::xcstrings_docx_merger::convert::apply_extracted;
+    use crate::xcstrings_docx_merger::docx_extractor::extract_from_rows;
+    use crate::xcstrings_docx_merger::states::StateMapping;
+    use swift_localizable_json_parser::types::input::{Translation, TranslationTypeContainer};
+
+    fn apply(rows: &[&[&str]], state_mapping: &StateMapping) -> Translation {
+        let mut parsed = swift_localizable_json_parser::parse_from_bytes(include_bytes!(
+            "../../resources/reader_test_base.xcstrings"
+        ))
+        .unwrap();
+        let rows = rows
+            .iter()
+            .map(|row| row.iter().map(|cell| cell.to_string()).collect())
+            .collect();
+
+        apply_extracted(
+            &mut parsed.translation,
+            &mut Default::default(),
+            extract_from_rows(rows, &[]).unwrap().remove(0),
+            state_mapping,
+        )
+        .unwrap();
+
+        parsed.translation
+    }
+
+    fn state_of(translation: &Translation, key: &str) -> String {
+        match &translation.strings[key].localizations["nl"] {
+            TranslationTypeContainer::StringUnit(su) => su.string_unit.state.clone(),
+            TranslationTypeContainer::Variation(_) => unreachable!(),
+        }
+    }
+
+    #[test]
+    fn test_state_mapping() {
+        let state_mapping = StateMapping::new(
+            "needs_review",
+            StatePrecedence::RowState,
+            &["approved=translated".to_string()],
+        )
+        .unwrap();
+        let translation = apply(
+            &[
+                &["Key", "Variation", "State", "en", "nl"],
+                &["normal_0", "N/A", "Approved", "normal", "normaal"],
+                &["normal_1", "N/A", "new", "normal", "normaal"],
+            ],
+            &state_mapping,
+        );
+
+        assert_eq!("translated", state_of(&translation, "normal_0"));
+        assert_eq!("needs_review", state_of(&translation, "normal_1"));
+        assert!(StateMapping::new("done", StatePrecedence::RowState, &[]).is_err());
+
+        let prefilled = Some("Prefilled");
+
+        assert_eq!(
+            "needs_review",
+            StateMapping::default()
+                .state_for("key", prefilled, "waarde", None)
+                .unwrap()
+        );
+        assert_eq!(
+            "translated",
+            StateMapping::new(
+                "translated",
+                StatePrecedence::RowState,
+                &["prefilled=translated".to_string()]
+            )
+            .unwrap()
+            .state_for("key", prefilled, "waarde", None)
+            .unwrap()
+        );
+    }
+
+    #[test]
+    fn test_import_state() {
+        // Without a State column only the changed translation gets the import state
+        let state_mapping =
+            StateMapping::new("needs_review", StatePrecedence::RowState, &[]).unwrap();
+        let translation = apply(
+            &[
+                &["Key", "Variation", "en", "nl"],
+                &["normal_0", "N/A", "normal", "normaal"],
+                &[
+                    "Inlined with an actual translation",
+                    "N/A",
+                    "",
+                    "Ingelijnd!",
+                ],
+            ],
+            &state_mapping,
+        );
+
+        assert_eq!("translated", state_of(&translation, "normal_0"));
+        assert_eq!(
+            "needs_review",
+            state_of(&translation, "Inlined with an actual translation")
+        );
+
+        // The precedence decides between the State column and the import state for changed translations
+        let rows: &[&[&str]] = &[
+            &["Key", "Variation", "State", "en", "nl"],
+            &["normal_0", "N/A", "translated", "normal", "normaal"],
+            &["normal_1", "N/A", "translated", "normal", "normaal"],
+        ];
+        let translation = apply(rows, &state_mapping);
+
+        assert_eq!("translated", state_of(&translation, "normal_1"));
+
+        let state_mapping =
+            StateMapping::new("needs_review", StatePrecedence::ImportState, &[]).unwrap();
+        let translation = apply(rows, &state_mapping);
+
+        assert_eq!("translated", state_of(&translation, "normal_0"));
+        assert_eq!("needs_review", state_of(&translation, "normal_1"));
+    }
+}
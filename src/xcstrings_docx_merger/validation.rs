@@ -0,0 +1,238 @@
+use crate::devices::{rows_with_devices, DeviceVariate, DeviceVariations};
+use crate::placeholders::{validate_placeholders, PlaceholderIssue};
+use crate::plural_rules::plural_categories;
+use crate::xcstrings_docx_merger::config::PlaceholderPolicy;
+use crate::xcstrings_docx_merger::docx_extractor::ExtractContainer;
+use crate::NEEDS_REVIEW_STATE;
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::{Display, Formatter};
+use swift_localizable_json_parser::types::output::{Parsed, PluralVariate};
+
+/// A translation with placeholders that don't match the base value
+#[derive(Clone, Debug, Serialize)]
+pub struct PlaceholderProblem {
+    pub language_code: String,
+    pub key: String,
+    pub variation: Option<String>,
+    pub issues: Vec<PlaceholderIssue>,
+}
+
+impl Display for PlaceholderProblem {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let issues = self
+            .issues
+            .iter()
+            .map(|i| i.to_string())
+            .collect::<Vec<_>>()
+            .join(", ");
+
+        match &self.variation {
+            None => write!(f, "{} ({}): {issues}", self.key, self.language_code),
+            Some(variation) => write!(
+                f,
+                "{} {variation} ({}): {issues}",
+                self.key, self.language_code
+            ),
+        }
+    }
+}
+
+/// Compares the placeholders of the extracted translations with the base values of the catalog, per key and variate.
+/// The policy decides what happens with the problematic rows, failing is up to the caller.
+pub(crate) fn validate_extracted(
+    parsed: &Parsed,
+    devices: &DeviceVariations,
+    extracted: &mut ExtractContainer,
+    policy: PlaceholderPolicy,
+) -> Vec<PlaceholderProblem> {
+    let base_values = rows_with_devices(
+        &parsed.localizable.localized_per_language(),
+        &parsed.translation.source_language,
+        devices,
+        &extracted.language_code,
+    )
+    .into_iter()
+    .map(|row| (row.unit_id(), row.base_value))
+    .collect::<HashMap<_, _>>();
+    let mut problems = vec![];
+
+    for extract in &mut extracted.extracted {
+        let base_value = match base_values.get(&extract.unit_id()) {
+            None => continue,
+            Some(base_value) => base_value,
+        };
+        let issues = validate_placeholders(base_value, extract.translated.trim());
+
+        if issues.is_empty() {
+            continue;
+        }
+
+        let problem = PlaceholderProblem {
+            language_code: extracted.language_code.clone(),
+            key: extract.key.clone(),
+            variation: extract.variation_text(),
+            issues,
+        };
+
+        log::warn!("Invalid placeholders: {problem}");
+
+        if policy == PlaceholderPolicy::NeedsReview {
+            extract.state = Some(NEEDS_REVIEW_STATE.to_string());
+        }
+
+        problems.push(problem);
+    }
+
+    problems
+}
+
+/// A plural key whose translated categories don't match the CLDR plural rules of the language
+#[derive(Clone, Debug, Serialize)]
+pub struct PluralProblem {
+    pub language_code: String,
+    pub key: String,
+    /// The device of the plural, for keys with device variations
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub device: Option<String>,
+    /// Categories the language needs, but that have no translation
+    pub missing: Vec<String>,
+    /// Translated categories the language doesn't use
+    pub unsupported: Vec<String>,
+}
+
+impl Display for PluralProblem {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let key = match &self.device {
+            None => self.key.clone(),
+            Some(device) => format!("{} {device}", self.key),
+        };
+
+        write!(
+            f,
+            "{key} ({}): missing [{}], unsupported [{}]",
+            self.language_code,
+            self.missing.join(", "),
+            self.unsupported.join(", ")
+        )
+    }
+}
+
+/// Compares the translated plural categories per key with the categories the language needs.
+/// Keys without any translation and languages without bundled rules are skipped, the translations are merged regardless.
+pub(crate) fn validate_plural_categories(extracted: &ExtractContainer) -> Vec<PluralProblem> {
+    let categories = match plural_categories(&extracted.language_code) {
+        None => return vec![],
+        Some(categories) => categories,
+    };
+    let mut translated_per_key =
+        BTreeMap::<(&str, Option<DeviceVariate>), Vec<&PluralVariate>>::new();
+
+    for extract in &extracted.extracted {
+        if let Some(variate) = &extract.variation {
+            let translated = translated_per_key
+                .entry((&extract.key, extract.device))
+                .or_default();
+
+            if !extract.translated.trim().is_empty() {
+                translated.push(variate);
+            }
+        }
+    }
+
+    let mut problems = vec![];
+
+    for ((key, device), translated) in translated_per_key {
+        if translated.is_empty() {
+            continue;
+        }
+
+        let problem = PluralProblem {
+            language_code: extracted.language_code.clone(),
+            key: key.to_string(),
+            device: device.map(|d| d.key().to_string()),
+            missing: categories
+                .iter()
+                .filter(|c| !translated.contains(c))
+                .map(|c| c.android_key().to_string())
+                .collect(),
+            unsupported: translated
+                .iter()
+                .filter(|t| !categories.contains(t))
+                .map(|t| t.android_key().to_string())
+                .collect(),
+        };
+
+        if problem.missing.is_empty() && problem.unsupported.is_empty() {
+            continue;
+        }
+
+        log::warn!("Plural categories don't match the plural rules: {problem}");
+
+        problems.push(problem);
+    }
+
+    problems
+}
+
+#[cfg(test)]
+mod test {
+    use crate::xcstrings_docx_merger::config::PlaceholderPolicy;
+    use crate::xcstrings_docx_merger::docx_extractor::extract_from_rows;
+    use crate::xcstrings_docx_merger::validation::{
+        validate_extracted, validate_plural_categories,
+    };
+    use crate::NEEDS_REVIEW_STATE;
+
+    #[test]
+    fn test_validate_extracted() {
+        let parsed = swift_localizable_json_parser::parse_from_bytes(include_bytes!(
+            "../../resources/reader_test_base.xcstrings"
+        ))
+        .unwrap();
+        let rows = [
+            ["Key", "Variation", "en", "nl"],
+            ["normal_0", "N/A", "normal", "normaal"],
+            ["plurals", "other", "%lld items", "items"],
+        ]
+        .map(|row| row.map(|cell| cell.to_string()).to_vec())
+        .to_vec();
+        let mut extracted = extract_from_rows(rows, &[]).unwrap().remove(0);
+        let problems = validate_extracted(
+            &parsed,
+            &Default::default(),
+            &mut extracted,
+            PlaceholderPolicy::NeedsReview,
+        );
+
+        assert_eq!(1, problems.len());
+        assert_eq!("plurals", problems[0].key);
+        assert_eq!(Some("other"), problems[0].variation.as_deref());
+        assert_eq!(None, extracted.extracted[0].state);
+        assert_eq!(
+            Some(NEEDS_REVIEW_STATE),
+            extracted.extracted[1].state.as_deref()
+        );
+    }
+
+    #[test]
+    fn test_validate_plural_categories() {
+        let rows = [
+            ["Key", "Variation", "en", "pl"],
+            ["files", "one", "%lld file", "%lld plik"],
+            ["files", "two", "%lld files", "%lld pliki"],
+            ["files", "other", "%lld files", "%lld pliku"],
+            ["empty", "one", "%lld item", ""],
+            ["empty", "few", "%lld items", ""],
+        ]
+        .map(|row| row.map(|cell| cell.to_string()).to_vec())
+        .to_vec();
+        let extracted = extract_from_rows(rows, &[]).unwrap().remove(0);
+        let problems = validate_plural_categories(&extracted);
+
+        assert_eq!(1, problems.len());
+        assert_eq!("files", problems[0].key);
+        assert_eq!(vec!["few", "many"], problems[0].missing);
+        assert_eq!(vec!["two"], problems[0].unsupported);
+    }
+}
@@ -0,0 +1,148 @@
+use crate::error::ConvertError;
+use crate::i18next_writer::convert::{JSON_EXTENSION, PLURAL_SUFFIX_SEPARATOR};
+use crate::placeholders::{from_named, plural_position};
+use crate::rows::variate_from_key;
+use crate::xcstrings_android_xml_merger::android_xml_extractor::AndroidString;
+use crate::xcstrings_android_xml_merger::convert::{base_value, merge_language, Export};
+use crate::xcstrings_docx_merger::convert::{read_xcstrings, write_xcstrings};
+use crate::xcstrings_i18next_merger::config::Config;
+use crate::xcstrings_i18next_merger::i18next_extractor::extract;
+use std::collections::{BTreeMap, HashSet};
+
+pub fn merge(config: Config) -> Result<Export, ConvertError> {
+    let mut parsed = read_xcstrings(&config.base_xcstrings)?;
+    let source_language = parsed.translation.source_language.clone();
+    let mut jsons = BTreeMap::new();
+
+    ConvertError::ensure_exists(&config.read_from)?;
+
+    let entries =
+        std::fs::read_dir(&config.read_from).map_err(ConvertError::io(&config.read_from))?;
+
+    for entry in entries {
+        let path = entry.map_err(ConvertError::io(&config.read_from))?.path();
+
+        if path.extension().map(|e| e == JSON_EXTENSION) != Some(true) {
+            continue;
+        }
+
+        match path.file_stem().map(|s| s.to_string_lossy().to_string()) {
+            Some(language_code) if language_code != source_language => {
+                jsons.insert(language_code, path);
+            }
+            _ => {
+                log::debug!("Skipping: {:#?}", path);
+            }
+        }
+    }
+
+    let mut languages = vec![];
+
+    for (language_code, json) in jsons {
+        log::debug!("Merging i18next language: {language_code}");
+
+        let names = parsed
+            .localizable
+            .single_translation
+            .iter()
+            .map(|s| s.key_alphanumeric.as_str())
+            .collect::<HashSet<_>>();
+        let strings = extract(&json)?
+            .into_iter()
+            .map(|(name, value)| to_android_string(&names, name, value))
+            .collect();
+
+        languages.push(merge_language(
+            &parsed.localizable,
+            &mut parsed.translation,
+            language_code,
+            strings,
+            |value, single_translation, variation| {
+                from_named(
+                    value,
+                    &base_value(single_translation, &source_language, variation),
+                    plural_position(single_translation, &source_language),
+                    "{{",
+                    "}}",
+                )
+            },
+        )?);
+    }
+
+    write_xcstrings(
+        &config.updated_xcstrings,
+        &parsed.translation,
+        &config.backups,
+    )?;
+
+    Ok(Export { languages })
+}
+
+/// A name can end with a plural suffix by itself, only strip it if the name without it is in the catalog
+fn to_android_string(names: &HashSet<&str>, name: String, value: String) -> AndroidString {
+    if !names.contains(name.as_str()) {
+        if let Some((plural_name, suffix)) = name.rsplit_once(PLURAL_SUFFIX_SEPARATOR) {
+            if let Some(variation) = variate_from_key(suffix) {
+                if names.contains(plural_name) {
+                    return AndroidString {
+                        name: plural_name.to_string(),
+                        variation: Some(variation),
+                        value,
+                    };
+                }
+            }
+        }
+    }
+
+    AndroidString {
+        name,
+        variation: None,
+        value,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::xcstrings_i18next_merger::config::Config;
+    use crate::xcstrings_i18next_merger::convert::merge;
+    use std::env::current_dir;
+
+    #[test]
+    fn test_merge() {
+        let base = current_dir().unwrap();
+        let save_in = base.join("generated").join("i18next_merge");
+        let locales = save_in.join("locales");
+        let xcstrings_updated = save_in.join("updated.xcstrings");
+
+        let _ = std::fs::remove_dir_all(&save_in);
+
+        crate::i18next_writer::convert::write_jsons(crate::i18next_writer::config::Config {
+            path_to_xcstrings: base.join("resources").join("reader_test_base.xcstrings"),
+            write_in: locales.clone(),
+        })
+        .unwrap();
+
+        // Web translators add the missing plural form
+        let nl = locales.join("nl.json");
+        let raw = std::fs::read_to_string(&nl).unwrap().replace(
+            "\"plurals_one\": \"{{count}} eentje\"",
+            "\"plurals_one\": \"{{count}} eentje\",\n  \"plurals_other\": \"{{count}} meerdere\"",
+        );
+
+        std::fs::write(&nl, raw).unwrap();
+
+        let export = merge(Config {
+            read_from: locales,
+            base_xcstrings: base.join("resources").join("reader_test_base.xcstrings"),
+            updated_xcstrings: xcstrings_updated.clone(),
+            backups: Default::default(),
+        })
+        .unwrap();
+
+        assert_eq!(1, export.languages.len());
+        assert!(export.languages[0].only_in_android.is_empty());
+        assert!(std::fs::read_to_string(xcstrings_updated)
+            .unwrap()
+            .contains("\"value\" : \"%1$lld meerdere\""));
+    }
+}
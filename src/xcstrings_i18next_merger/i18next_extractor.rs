@@ -0,0 +1,48 @@
+use std::path::PathBuf;
+
+use crate::error::ConvertError;
+use serde_json::{Map, Value};
+
+/// The flat key value pairs of an i18next json file, nested objects are joined with a dot
+pub fn extract(json: &PathBuf) -> Result<Vec<(String, String)>, ConvertError> {
+    if json.exists() {
+        log::debug!("json file exists...");
+    } else {
+        return Err(ConvertError::MissingFile { path: json.clone() });
+    }
+
+    extract_from_raw(&std::fs::read_to_string(json).map_err(ConvertError::io(json))?)
+}
+
+pub fn extract_from_raw(raw: &str) -> Result<Vec<(String, String)>, ConvertError> {
+    let json: Map<String, Value> = serde_json::from_str(raw)?;
+    let mut extracted = vec![];
+
+    flatten("", json, &mut extracted)?;
+
+    Ok(extracted)
+}
+
+fn flatten(
+    prefix: &str,
+    object: Map<String, Value>,
+    extracted: &mut Vec<(String, String)>,
+) -> Result<(), ConvertError> {
+    for (key, value) in object {
+        let key = if prefix.is_empty() {
+            key
+        } else {
+            format!("{prefix}.{key}")
+        };
+
+        match value {
+            Value::String(value) => extracted.push((key, value)),
+            Value::Object(object) => flatten(&key, object, extracted)?,
+            _ => {
+                exit_with_log!(format!("Expected a string or object for: {key}"));
+            }
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,3 @@
+pub mod config;
+pub mod convert;
+pub mod strings_extractor;
@@ -0,0 +1,24 @@
+use crate::backup::Backups;
+use clap::Parser;
+use std::path::PathBuf;
+
+#[derive(Clone, Debug, Parser)]
+pub struct Config {
+    /// The directory which contains the *.lproj directories
+    #[clap(long)]
+    pub read_from: PathBuf,
+    /// The name of the .strings and .stringsdict files, without extension
+    #[clap(long, default_value = "Localizable")]
+    pub table_name: String,
+    /// The source language of the catalog, only used if there is no base .xcstrings file
+    #[clap(long, default_value = "en")]
+    pub source_language: String,
+    /// The path to an existing .xcstrings file to extend, a new catalog is created if omitted
+    #[clap(long)]
+    pub base_xcstrings: Option<PathBuf>,
+    /// The path to an existing or non-existing .xcstrings file to write the catalog to
+    #[clap(long)]
+    pub updated_xcstrings: PathBuf,
+    #[clap(flatten)]
+    pub backups: Backups,
+}
@@ -0,0 +1,230 @@
+use crate::error::ConvertError;
+use crate::xcstrings_docx_merger::convert::{plural_field, read_xcstrings, write_xcstrings};
+use crate::xcstrings_legacy_strings_merger::config::Config;
+use crate::xcstrings_legacy_strings_merger::strings_extractor::{
+    extract_strings, extract_stringsdict,
+};
+use serde::Serialize;
+use std::collections::hash_map::Entry;
+use std::collections::HashMap;
+use swift_localizable_json_parser::types::inoutoutput::{StringUnitContainer, TranslationValue};
+use swift_localizable_json_parser::types::input::{
+    Language, Translation, TranslationTypeContainer, VariationContainer,
+};
+use swift_localizable_json_parser::TRANSLATED_STATE;
+
+pub const LPROJ_EXTENSION: &str = "lproj";
+/// Base.lproj contains the strings of the source language
+const BASE_LPROJ: &str = "Base";
+const XCSTRINGS_VERSION: &str = "1.0";
+
+#[derive(Clone, Debug, Default, Serialize)]
+pub struct Export {
+    pub languages: Vec<String>,
+    pub keys_added: i32,
+    pub localizations_added: i32,
+    /// Localizations which are already in the catalog, these are never overwritten
+    pub localizations_skipped: i32,
+    /// .stringsdict entries with multiple variables, these need substitutions which aren't supported
+    pub unsupported_keys: Vec<String>,
+}
+
+pub fn merge(config: Config) -> Result<Export, ConvertError> {
+    let mut translation = match &config.base_xcstrings {
+        Some(base_xcstrings) => read_xcstrings(base_xcstrings)?.translation,
+        None => Translation {
+            source_language: config.source_language.clone(),
+            strings: HashMap::new(),
+            version: XCSTRINGS_VERSION.to_string(),
+        },
+    };
+    let source_language = translation.source_language.clone();
+    let mut lprojs = vec![];
+
+    ConvertError::ensure_exists(&config.read_from)?;
+
+    let entries =
+        std::fs::read_dir(&config.read_from).map_err(ConvertError::io(&config.read_from))?;
+
+    for entry in entries {
+        let path = entry.map_err(ConvertError::io(&config.read_from))?.path();
+
+        if path.extension().map(|e| e == LPROJ_EXTENSION) != Some(true) {
+            continue;
+        }
+
+        let language = match path.file_stem().map(|s| s.to_string_lossy().to_string()) {
+            Some(stem) if stem == BASE_LPROJ => source_language.clone(),
+            Some(stem) => stem,
+            None => continue,
+        };
+
+        lprojs.push((language, path));
+    }
+
+    // The source language first, so its comments are preferred
+    lprojs.sort_by_key(|(language, _)| (language != &source_language, language.clone()));
+
+    let mut export = Export::default();
+
+    for (language, lproj) in lprojs {
+        log::debug!("Migrating language: {language}");
+
+        let strings = lproj.join(format!("{}.strings", config.table_name));
+        let stringsdict = lproj.join(format!("{}.stringsdict", config.table_name));
+
+        // Plurals first, a key in both files is a plural at runtime
+        if stringsdict.exists() {
+            let (plurals, unsupported_keys) = extract_stringsdict(&stringsdict)?;
+
+            for plural in plurals {
+                let mut container = VariationContainer::default();
+
+                for (variate, value) in plural.variations {
+                    *plural_field(&mut container.variations.plural, &variate) =
+                        Some(translated(value));
+                }
+
+                add_localization(
+                    &mut translation,
+                    &mut export,
+                    &language,
+                    &plural.key,
+                    "",
+                    TranslationTypeContainer::Variation(container),
+                );
+            }
+
+            export.unsupported_keys.extend(unsupported_keys);
+        }
+
+        if strings.exists() {
+            for string in extract_strings(&strings)? {
+                add_localization(
+                    &mut translation,
+                    &mut export,
+                    &language,
+                    &string.key,
+                    &string.comment,
+                    TranslationTypeContainer::StringUnit(translated(string.value)),
+                );
+            }
+        }
+
+        export.languages.push(language);
+    }
+
+    export.unsupported_keys.sort();
+    export.unsupported_keys.dedup();
+
+    write_xcstrings(&config.updated_xcstrings, &translation, &config.backups)?;
+
+    Ok(export)
+}
+
+fn translated(value: String) -> StringUnitContainer {
+    StringUnitContainer {
+        string_unit: TranslationValue {
+            state: TRANSLATED_STATE.to_string(),
+            value,
+        },
+    }
+}
+
+fn add_localization(
+    translation: &mut Translation,
+    export: &mut Export,
+    language: &str,
+    key: &str,
+    comment: &str,
+    container: TranslationTypeContainer,
+) {
+    let is_source_language = language == translation.source_language;
+    let entry = translation
+        .strings
+        .entry(key.to_string())
+        .or_insert_with(|| {
+            export.keys_added += 1;
+
+            Language {
+                comment: Default::default(),
+                localizations: Default::default(),
+            }
+        });
+
+    if entry.comment.is_empty() {
+        entry.comment = comment.to_string();
+    }
+
+    if let TranslationTypeContainer::StringUnit(su) = &container {
+        // Just like Xcode, the source language is inlined if the value equals the key
+        if is_source_language && su.string_unit.value == key {
+            return;
+        }
+    }
+
+    match entry.localizations.entry(language.to_string()) {
+        Entry::Occupied(_) => export.localizations_skipped += 1,
+        Entry::Vacant(vacant) => {
+            vacant.insert(container);
+
+            export.localizations_added += 1;
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::xcstrings_legacy_strings_merger::config::Config;
+    use crate::xcstrings_legacy_strings_merger::convert::merge;
+    use std::env::current_dir;
+    use swift_localizable_json_parser::types::output::Translation;
+
+    #[test]
+    fn test_merge() {
+        let base = current_dir().unwrap();
+        let save_in = base.join("generated").join("legacy_strings_merge");
+        let xcstrings_updated = save_in.join("updated.xcstrings");
+
+        std::fs::create_dir_all(&save_in).unwrap();
+
+        let export = merge(Config {
+            read_from: base.join("resources").join("legacy"),
+            table_name: "Localizable".to_string(),
+            source_language: "en".to_string(),
+            base_xcstrings: None,
+            updated_xcstrings: xcstrings_updated.clone(),
+            backups: Default::default(),
+        })
+        .unwrap();
+
+        assert_eq!(vec!["en".to_string(), "nl".to_string()], export.languages);
+        assert_eq!(
+            vec!["distance_and_time".to_string()],
+            export.unsupported_keys
+        );
+
+        let parsed = swift_localizable_json_parser::parse_from_file(&xcstrings_updated).unwrap();
+        let single_translation = parsed
+            .localizable
+            .single_translation
+            .iter()
+            .find(|s| s.key_raw == "%lld items")
+            .unwrap();
+
+        assert_eq!("The amount of items", single_translation.comment);
+
+        match single_translation
+            .localization_value
+            .language_translation
+            .get("nl")
+            .unwrap()
+        {
+            Translation::PluralVariation(pv) => {
+                assert_eq!(2, pv.len());
+                assert_eq!("Je hebt %lld items", pv[1].translation_value.value);
+            }
+            Translation::Localization(_) => panic!(),
+        }
+    }
+}
@@ -0,0 +1,302 @@
+use std::path::PathBuf;
+use std::sync::LazyLock;
+
+use crate::error::ConvertError;
+use crate::rows::variate_from_key;
+use crate::xml::text_of;
+use regex::Regex;
+use swift_localizable_json_parser::types::output::PluralVariate;
+
+pub const FORMAT_KEY: &str = "NSStringLocalizedFormatKey";
+/// The variables of a format, e.g.: %#@count@
+static VARIABLE_REGEX: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"%#@([^@]+)@").unwrap());
+
+/// A single entry of a .strings file
+#[derive(Clone, Debug)]
+pub struct LegacyString {
+    pub key: String,
+    pub value: String,
+    pub comment: String,
+}
+
+/// A single entry of a .stringsdict file
+#[derive(Clone, Debug)]
+pub struct LegacyPlural {
+    pub key: String,
+    pub variations: Vec<(PluralVariate, String)>,
+}
+
+pub fn extract_strings(strings: &PathBuf) -> Result<Vec<LegacyString>, ConvertError> {
+    let raw = std::fs::read(strings).map_err(ConvertError::io(strings))?;
+
+    extract_strings_from_raw(&decode(raw)?)
+}
+
+/// .strings files are often UTF-16 encoded, which is recognizable by the byte order mark
+fn decode(raw: Vec<u8>) -> Result<String, ConvertError> {
+    let utf16 = |raw: &[u8], from_bytes: fn([u8; 2]) -> u16| {
+        let units = raw
+            .chunks_exact(2)
+            .map(|c| from_bytes([c[0], c[1]]))
+            .collect::<Vec<_>>();
+
+        String::from_utf16(&units).map_err(invalid_encoding)
+    };
+
+    match raw.as_slice() {
+        [0xFF, 0xFE, rest @ ..] => utf16(rest, u16::from_le_bytes),
+        [0xFE, 0xFF, rest @ ..] => utf16(rest, u16::from_be_bytes),
+        [0xEF, 0xBB, 0xBF, rest @ ..] => String::from_utf8(rest.to_vec()).map_err(invalid_encoding),
+        _ => String::from_utf8(raw).map_err(invalid_encoding),
+    }
+}
+
+fn invalid_encoding(error: impl std::error::Error + Send + Sync + 'static) -> ConvertError {
+    ConvertError::Parse {
+        format: "strings",
+        source: Box::new(error),
+    }
+}
+
+pub fn extract_strings_from_raw(raw: &str) -> Result<Vec<LegacyString>, ConvertError> {
+    let mut chars = raw.chars().peekable();
+    let mut extracted = vec![];
+    let mut comment = String::new();
+    let mut key: Option<String> = None;
+    let mut value: Option<String> = None;
+
+    macro_rules! read_quoted {
+        () => {{
+            let mut quoted = String::new();
+
+            loop {
+                match chars.next() {
+                    None => {
+                        exit_with_log!("Unterminated string in .strings file");
+                    }
+                    Some('"') => break,
+                    Some('\\') => match chars.next() {
+                        Some('n') => quoted.push('\n'),
+                        Some('t') => quoted.push('\t'),
+                        Some('r') => quoted.push('\r'),
+                        Some('U') | Some('u') => {
+                            let hex = chars.by_ref().take(4).collect::<String>();
+
+                            match u32::from_str_radix(&hex, 16).ok().and_then(char::from_u32) {
+                                Some(c) => quoted.push(c),
+                                None => {
+                                    exit_with_log!(format!("Invalid unicode escape: {hex}"));
+                                }
+                            }
+                        }
+                        Some(c) => quoted.push(c),
+                        None => {
+                            exit_with_log!("Unterminated escape in .strings file");
+                        }
+                    },
+                    Some(c) => quoted.push(c),
+                }
+            }
+
+            quoted
+        }};
+    }
+
+    while let Some(c) = chars.next() {
+        match c {
+            c if c.is_whitespace() => continue,
+            '/' if chars.peek() == Some(&'*') => {
+                chars.next();
+
+                let mut block = String::new();
+
+                while let Some(c) = chars.next() {
+                    if c == '*' && chars.peek() == Some(&'/') {
+                        chars.next();
+
+                        break;
+                    }
+
+                    block.push(c);
+                }
+
+                comment = block.trim().to_string();
+            }
+            '/' if chars.peek() == Some(&'/') => {
+                let line = chars
+                    .by_ref()
+                    .take_while(|c| *c != '\n')
+                    .collect::<String>();
+
+                comment = line.trim_start_matches('/').trim().to_string();
+            }
+            '"' => {
+                let quoted = read_quoted!();
+
+                if key.is_none() {
+                    key = Some(quoted);
+                } else {
+                    value = Some(quoted);
+                }
+            }
+            '=' if key.is_some() => continue,
+            ';' => {
+                let key = match key.take() {
+                    None => {
+                        exit_with_log!("Found ; without a key in .strings file");
+                    }
+                    Some(key) => key,
+                };
+
+                extracted.push(LegacyString {
+                    // A key without a value is its own value
+                    value: value.take().unwrap_or(key.clone()),
+                    key,
+                    comment: std::mem::take(&mut comment),
+                });
+            }
+            c if c.is_alphanumeric() || "_.$:/-".contains(c) => {
+                // Old style plists allow unquoted keys and values
+                let mut unquoted = c.to_string();
+
+                while let Some(c) = chars.next_if(|c| c.is_alphanumeric() || "_.$:/-".contains(*c))
+                {
+                    unquoted.push(c);
+                }
+
+                if key.is_none() {
+                    key = Some(unquoted);
+                } else {
+                    value = Some(unquoted);
+                }
+            }
+            c => {
+                exit_with_log!(format!("Unexpected character in .strings file: {c}"));
+            }
+        }
+    }
+
+    if let Some(key) = key {
+        exit_with_log!(format!("Missing ; after key: {key}"));
+    }
+
+    Ok(extracted)
+}
+
+/// The plurals and the keys which can not be represented in a catalog without substitutions
+pub fn extract_stringsdict(
+    stringsdict: &PathBuf,
+) -> Result<(Vec<LegacyPlural>, Vec<String>), ConvertError> {
+    extract_stringsdict_from_raw(
+        &std::fs::read_to_string(stringsdict).map_err(ConvertError::io(stringsdict))?,
+    )
+}
+
+pub fn extract_stringsdict_from_raw(
+    raw: &str,
+) -> Result<(Vec<LegacyPlural>, Vec<String>), ConvertError> {
+    // Plists always have a DOCTYPE
+    let document = roxmltree::Document::parse_with_options(
+        raw,
+        roxmltree::ParsingOptions {
+            allow_dtd: true,
+            ..Default::default()
+        },
+    )?;
+    let root = match document
+        .root_element()
+        .children()
+        .find(|n| n.has_tag_name("dict"))
+    {
+        None => {
+            exit_with_log!("There is no dict in the .stringsdict file");
+        }
+        Some(root) => root,
+    };
+    let mut extracted = vec![];
+    let mut unsupported = vec![];
+
+    for (key, entry) in dict_entries(root) {
+        let entry_values = dict_entries(entry);
+        let format = entry_values
+            .iter()
+            .find(|(k, _)| k == FORMAT_KEY)
+            .map(|(_, v)| text_of(*v))
+            .unwrap_or_default();
+        let variables = VARIABLE_REGEX
+            .captures_iter(&format)
+            .map(|c| c[1].to_string())
+            .collect::<Vec<_>>();
+
+        // Multiple variables need substitutions, which the catalog parser does not support
+        let variable = match variables.as_slice() {
+            [variable] => variable,
+            _ => {
+                log::warn!("Unsupported .stringsdict entry: {key}");
+
+                unsupported.push(key);
+
+                continue;
+            }
+        };
+        let rule = match entry_values.iter().find(|(k, _)| k == variable) {
+            None => {
+                unsupported.push(key);
+
+                continue;
+            }
+            Some((_, rule)) => *rule,
+        };
+        let mut variations = vec![];
+
+        for (category, value) in dict_entries(rule) {
+            if let Some(variate) = variate_from_key(&category) {
+                variations.push((
+                    variate,
+                    format.replacen(&format!("%#@{variable}@"), &text_of(value), 1),
+                ));
+            }
+        }
+
+        extracted.push(LegacyPlural { key, variations });
+    }
+
+    Ok((extracted, unsupported))
+}
+
+/// The key and value elements of a plist dict
+fn dict_entries<'a, 'input>(
+    dict: roxmltree::Node<'a, 'input>,
+) -> Vec<(String, roxmltree::Node<'a, 'input>)> {
+    let mut entries = vec![];
+    let mut key = None;
+
+    for node in dict.children().filter(|n| n.is_element()) {
+        if node.has_tag_name("key") {
+            key = Some(text_of(node));
+        } else if let Some(key) = key.take() {
+            entries.push((key, node));
+        }
+    }
+
+    entries
+}
+
+#[cfg(test)]
+mod test {
+    use super::extract_strings_from_raw;
+
+    #[test]
+    fn test_extract_strings() {
+        let extracted = extract_strings_from_raw(
+            "/* The title */\n\"title\" = \"Hello \\\"World\\\"\\n\";\n// Unquoted\nkey = value;",
+        )
+        .unwrap();
+
+        assert_eq!(2, extracted.len());
+        assert_eq!("Hello \"World\"\n", extracted[0].value);
+        assert_eq!("The title", extracted[0].comment);
+        assert_eq!("value", extracted[1].value);
+        assert_eq!("Unquoted", extracted[1].comment);
+    }
+}
@@ -0,0 +1,472 @@
+use crate::devices::{device_variation_text, rows_with_devices, DeviceVariate, DeviceVariations};
+use crate::error::ConvertError;
+use crate::placeholders::specifiers;
+use crate::plural_rules::plural_categories;
+use crate::rows::{languages_to_write, Row};
+use crate::xcstrings_docx_merger::convert::read_xcstrings_with_devices;
+use crate::xcstrings_lint::config::{Config, LintCheck};
+use crate::xcstrings_metadata::read::extract_from_parsed;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use swift_localizable_json_parser::types::output::{Parsed, PluralVariate, Translation};
+
+/// Translations of base values shorter than this vary too much in length to flag them as too long
+const MIN_LENGTH_TO_COMPARE: usize = 10;
+/// Base values with at most this many words are ambiguous without a comment, e.g.: Open
+const MAX_WORDS_WITHOUT_CONTEXT: usize = 2;
+const BRACKETS: [(char, char); 4] = [('(', ')'), ('[', ']'), ('{', '}'), ('«', '»')];
+/// Quotes that open and close with the same character, or with characters that differ per language, e.g.: “…” and „…“
+const QUOTES: [&[char]; 2] = [&['"'], &['“', '”', '„']];
+
+#[derive(Clone, Debug, Serialize)]
+pub struct Export {
+    pub base_language: String,
+    pub languages: Vec<LanguageReport>,
+    /// Issues of the keys themselves, independent of the languages
+    pub key_issues: Vec<Issue>,
+    pub issue_count: usize,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct LanguageReport {
+    pub language_code: String,
+    pub localized_keys: usize,
+    pub not_localized_keys: usize,
+    pub issues: Vec<Issue>,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct Issue {
+    pub check: LintCheck,
+    pub key: String,
+    pub variation: Option<String>,
+    pub message: String,
+}
+
+pub fn lint(config: Config) -> Result<Export, ConvertError> {
+    let (parsed, devices) = read_xcstrings_with_devices(&config.path_to_xcstrings)?;
+
+    lint_parsed(&parsed, &devices, &config)
+}
+
+pub fn lint_parsed(
+    parsed: &Parsed,
+    devices: &DeviceVariations,
+    config: &Config,
+) -> Result<Export, ConvertError> {
+    let metadata = extract_from_parsed(parsed)?;
+    let base_language = &parsed.translation.source_language;
+    let localized_per_language = parsed.localizable.localized_per_language();
+    // A language can have translations of keys with device variations only
+    let mut languages =
+        languages_to_write(&localized_per_language, base_language, devices.languages())
+            .into_iter()
+            .filter(|language| config.language.is_empty() || config.language.contains(language))
+            .collect::<Vec<_>>();
+
+    languages.sort();
+
+    let mut reports = vec![];
+
+    for language in languages {
+        let (localized_keys, not_localized_keys) = metadata
+            .export
+            .iter()
+            .find(|export| export.language_code == language)
+            .map(|export| (export.localized_keys, export.not_localized_keys))
+            .unwrap_or_default();
+        let rows = rows_with_devices(&localized_per_language, base_language, devices, &language);
+        let issues = lint_rows(&rows, base_language, &language, config)
+            .into_iter()
+            .filter(|issue| !config.skip.contains(&issue.check))
+            .collect();
+
+        reports.push(LanguageReport {
+            language_code: language,
+            localized_keys,
+            not_localized_keys,
+            issues,
+        });
+    }
+
+    let key_issues = if config.skip.contains(&LintCheck::EmptyComment) {
+        vec![]
+    } else {
+        lint_comments(parsed)
+    };
+    let issue_count = key_issues.len() + reports.iter().map(|r| r.issues.len()).sum::<usize>();
+
+    Ok(Export {
+        base_language: metadata.base_language,
+        languages: reports,
+        key_issues,
+        issue_count,
+    })
+}
+
+fn lint_rows(rows: &[Row], base_language: &str, language: &str, config: &Config) -> Vec<Issue> {
+    let categories = plural_categories(language);
+    // Regional variants of the base language may keep the base value as is, e.g.: en-GB
+    let same_language = primary_language(base_language) == primary_language(language);
+    // Empty categories of partly translated plurals are missing categories rather than untranslated keys
+    let mut translated_per_key =
+        BTreeMap::<(&str, Option<DeviceVariate>), Vec<&PluralVariate>>::new();
+    let mut issues = vec![];
+
+    for row in rows {
+        if let Some(variate) = &row.variation {
+            if !row.translated_value.trim().is_empty() {
+                translated_per_key
+                    .entry((&row.key, row.device))
+                    .or_default()
+                    .push(variate);
+            }
+        }
+    }
+
+    for row in rows {
+        let mut issue = |check: LintCheck, message: String| {
+            issues.push(Issue {
+                check,
+                key: row.key.clone(),
+                variation: (row.variation.is_some() || row.device.is_some())
+                    .then(|| row.variation_text()),
+                message,
+            })
+        };
+
+        if let Some(variate) = &row.variation {
+            // The base language may have categories this language doesn't use, e.g.: one for Japanese
+            if categories.is_some_and(|c| !c.contains(variate)) {
+                continue;
+            }
+        }
+
+        let base = row.base_value.as_str();
+        let translated = row.translated_value.as_str();
+
+        if translated.trim().is_empty() {
+            if row.variation.is_some()
+                && translated_per_key.contains_key(&(row.key.as_str(), row.device))
+            {
+                continue;
+            }
+
+            issue(
+                LintCheck::Untranslated,
+                "The translation is empty".to_string(),
+            );
+
+            continue;
+        }
+
+        if base.is_empty() {
+            continue;
+        }
+
+        if translated == base && !same_language && base.chars().any(char::is_alphabetic) {
+            issue(
+                LintCheck::IdenticalToSource,
+                "The translation is the same as the base value".to_string(),
+            );
+        }
+
+        if leading_whitespace(base) != leading_whitespace(translated) {
+            issue(
+                LintCheck::WhitespaceMismatch,
+                format!(
+                    "The base value starts with {:?}, the translation with {:?}",
+                    leading_whitespace(base),
+                    leading_whitespace(translated)
+                ),
+            );
+        }
+
+        if trailing_whitespace(base) != trailing_whitespace(translated) {
+            issue(
+                LintCheck::WhitespaceMismatch,
+                format!(
+                    "The base value ends with {:?}, the translation with {:?}",
+                    trailing_whitespace(base),
+                    trailing_whitespace(translated)
+                ),
+            );
+        }
+
+        let unbalanced_base = unbalanced(base);
+
+        for unbalanced in unbalanced(translated) {
+            if !unbalanced_base.contains(&unbalanced) {
+                issue(
+                    LintCheck::UnbalancedPunctuation,
+                    format!("The translation has unbalanced {unbalanced}"),
+                );
+            }
+        }
+
+        let base_length = base.chars().count();
+        let translated_length = translated.chars().count();
+
+        if base_length >= MIN_LENGTH_TO_COMPARE
+            && translated_length as f64 > base_length as f64 * config.max_length_ratio
+        {
+            issue(
+                LintCheck::TooLong,
+                format!(
+                    "The translation has {translated_length} characters, the base value {base_length}"
+                ),
+            );
+        }
+    }
+
+    if let Some(categories) = categories {
+        for ((key, device), variates) in translated_per_key {
+            for category in categories {
+                if variates.contains(&category) {
+                    continue;
+                }
+
+                issues.push(Issue {
+                    check: LintCheck::MissingPluralCategory,
+                    key: key.to_string(),
+                    variation: Some(match device {
+                        None => category.android_key().to_string(),
+                        Some(device) => device_variation_text(device, Some(category)),
+                    }),
+                    message: format!(
+                        "{language} needs the plural category {}",
+                        category.android_key()
+                    ),
+                });
+            }
+        }
+    }
+
+    issues
+}
+
+/// Keys without comment of which the base value needs context to translate it
+fn lint_comments(parsed: &Parsed) -> Vec<Issue> {
+    let localized_per_language = parsed.localizable.localized_per_language();
+    let base = match localized_per_language
+        .language_localized
+        .get(&parsed.translation.source_language)
+    {
+        None => return vec![],
+        Some(base) => base,
+    };
+    let mut issues = vec![];
+
+    for localized in &base.translations {
+        if !localized.comment.trim().is_empty() {
+            continue;
+        }
+
+        let reason = match &localized.translation {
+            Translation::Localization(l) if !specifiers(&l.value).is_empty() => "has placeholders",
+            Translation::Localization(l)
+                if l.value.split_whitespace().count() <= MAX_WORDS_WITHOUT_CONTEXT =>
+            {
+                "is too short to translate without context"
+            }
+            Translation::Localization(_) => continue,
+            Translation::PluralVariation(_) => "has plural variations",
+        };
+
+        issues.push(Issue {
+            check: LintCheck::EmptyComment,
+            key: localized.key_raw.clone(),
+            variation: None,
+            message: format!("The key has no comment, while the base value {reason}"),
+        });
+    }
+
+    issues
+}
+
+fn primary_language(language_code: &str) -> String {
+    language_code
+        .split(['-', '_'])
+        .next()
+        .unwrap_or_default()
+        .to_lowercase()
+}
+
+fn leading_whitespace(value: &str) -> &str {
+    &value[..value.len() - value.trim_start().len()]
+}
+
+fn trailing_whitespace(value: &str) -> &str {
+    &value[value.trim_end().len()..]
+}
+
+/// The brackets and quotes that don't have a counterpart, e.g.: ( for "(optional"
+fn unbalanced(value: &str) -> Vec<String> {
+    let count = |c: char| value.chars().filter(|v| *v == c).count();
+    let mut unbalanced = vec![];
+
+    for (open, close) in BRACKETS {
+        if count(open) != count(close) {
+            unbalanced.push(format!("{open}{close}"));
+        }
+    }
+
+    for quotes in QUOTES {
+        if quotes.iter().map(|q| count(*q)).sum::<usize>() % 2 != 0 {
+            unbalanced.push(quotes.iter().collect());
+        }
+    }
+
+    unbalanced
+}
+
+#[cfg(test)]
+mod test {
+    use crate::rows::Row;
+    use crate::xcstrings_lint::check::{lint, lint_parsed, lint_rows};
+    use crate::xcstrings_lint::config::{Config, LintCheck};
+    use swift_localizable_json_parser::types::output::PluralVariate;
+
+    fn config() -> Config {
+        Config {
+            path_to_xcstrings: Default::default(),
+            language: vec![],
+            skip: vec![],
+            max_length_ratio: 2.0,
+        }
+    }
+
+    fn row(variation: Option<PluralVariate>, base_value: &str, translated_value: &str) -> Row {
+        Row {
+            key: "key".to_string(),
+            comment: "".to_string(),
+            variation,
+            device: None,
+            state: "translated".to_string(),
+            base_value: base_value.to_string(),
+            translated_value: translated_value.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_lint_rows() {
+        let checks = |rows: &[Row], language: &str| {
+            lint_rows(rows, "en", language, &config())
+                .into_iter()
+                .map(|issue| issue.check)
+                .collect::<Vec<_>>()
+        };
+
+        assert!(checks(&[row(None, "Hello world", "Hallo wereld")], "nl").is_empty());
+        assert_eq!(
+            vec![LintCheck::Untranslated],
+            checks(&[row(None, "Hello", " ")], "nl")
+        );
+        assert_eq!(
+            vec![LintCheck::IdenticalToSource],
+            checks(&[row(None, "Menu", "Menu")], "nl")
+        );
+        assert!(checks(&[row(None, "Colour", "Colour")], "en-GB").is_empty());
+        assert_eq!(
+            vec![LintCheck::WhitespaceMismatch],
+            checks(&[row(None, "Name: ", "Naam:")], "nl")
+        );
+        assert_eq!(
+            vec![LintCheck::UnbalancedPunctuation],
+            checks(&[row(None, "Save (now)", "Opslaan (nu")], "nl")
+        );
+        assert!(checks(&[row(None, "Say “hi”", "Sag „hallo“")], "de").is_empty());
+        assert_eq!(
+            vec![LintCheck::TooLong],
+            checks(
+                &[
+                    row(None, "Save file", "Bestand opslaan"),
+                    row(None, "Save a file", "Een bestand heel erg lang opslaan")
+                ],
+                "nl"
+            )
+        );
+        assert_eq!(
+            vec![
+                LintCheck::MissingPluralCategory,
+                LintCheck::MissingPluralCategory
+            ],
+            checks(
+                &[
+                    row(Some(PluralVariate::One), "%lld file", "%lld plik"),
+                    row(Some(PluralVariate::Few), "%lld files", ""),
+                    row(Some(PluralVariate::Other), "%lld files", "%lld pliki")
+                ],
+                "pl"
+            )
+        );
+        // Japanese has no one category, so it doesn't have to be translated
+        assert!(checks(
+            &[
+                row(Some(PluralVariate::One), "%lld file", ""),
+                row(Some(PluralVariate::Other), "%lld files", "%lld ファイル")
+            ],
+            "ja"
+        )
+        .is_empty());
+    }
+
+    #[test]
+    fn test_lint() {
+        let parsed = swift_localizable_json_parser::parse_from_bytes(include_bytes!(
+            "../../resources/reader_test_base.xcstrings"
+        ))
+        .unwrap();
+        let export = lint_parsed(&parsed, &Default::default(), &config()).unwrap();
+
+        assert_eq!("en", export.base_language);
+        assert!(export.issue_count > 0);
+        assert!(!export.languages.is_empty());
+
+        let mut skip_all = config();
+
+        skip_all.skip = vec![
+            LintCheck::Untranslated,
+            LintCheck::IdenticalToSource,
+            LintCheck::WhitespaceMismatch,
+            LintCheck::UnbalancedPunctuation,
+            LintCheck::MissingPluralCategory,
+            LintCheck::TooLong,
+            LintCheck::EmptyComment,
+        ];
+
+        assert_eq!(
+            0,
+            lint_parsed(&parsed, &Default::default(), &skip_all)
+                .unwrap()
+                .issue_count
+        );
+    }
+
+    #[test]
+    fn test_lint_devices() {
+        let export = lint(Config {
+            path_to_xcstrings: std::env::current_dir()
+                .unwrap()
+                .join("resources")
+                .join("device_test_base.xcstrings"),
+            ..config()
+        })
+        .unwrap();
+        let nl = export
+            .languages
+            .iter()
+            .find(|l| l.language_code == "nl")
+            .unwrap();
+        let untranslated = nl
+            .issues
+            .iter()
+            .filter(|i| i.check == LintCheck::Untranslated)
+            .map(|i| (i.key.as_str(), i.variation.as_deref()))
+            .collect::<Vec<_>>();
+
+        // The translated mac variation is not an issue
+        assert!(untranslated.contains(&("tap", Some("device.iphone"))));
+        assert!(!untranslated.contains(&("tap", Some("device.mac"))));
+    }
+}
@@ -0,0 +1,36 @@
+use clap::Parser;
+use serde::Serialize;
+use std::path::PathBuf;
+
+#[derive(Clone, Debug, Parser)]
+pub struct Config {
+    pub path_to_xcstrings: PathBuf,
+    /// Only lints these languages, every language of the catalog if not set
+    #[clap(long)]
+    pub language: Vec<String>,
+    /// The checks to leave out, e.g. identical-to-source for catalogs with a lot of brand names
+    #[clap(long, value_enum)]
+    pub skip: Vec<LintCheck>,
+    /// A translation is too long if it has more than this times the characters of the base value
+    #[clap(long, default_value_t = 2.0)]
+    pub max_length_ratio: f64,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, clap::ValueEnum)]
+#[serde(rename_all = "snake_case")]
+pub enum LintCheck {
+    /// The translation is empty
+    Untranslated,
+    /// The translation is the same as the base value
+    IdenticalToSource,
+    /// The translation starts or ends with other whitespace than the base value
+    WhitespaceMismatch,
+    /// The translation has unbalanced brackets or quotes, while the base value has not
+    UnbalancedPunctuation,
+    /// A plural category the language needs is not in the catalog
+    MissingPluralCategory,
+    /// The translation is a lot longer than the base value
+    TooLong,
+    /// A key without a comment, while the base value is too short or has placeholders to translate it without context
+    EmptyComment,
+}
@@ -3,7 +3,7 @@ use crate::xcstrings_metadata::config::Config;
 use serde::Serialize;
 use std::collections::HashSet;
 use std::path::PathBuf;
-use swift_localizable_json_parser::types::output::Translation;
+use swift_localizable_json_parser::types::output::{Parsed, Translation};
 use swift_localizable_json_parser::TRANSLATED_STATE;
 
 #[derive(Debug, Clone, Serialize)]
@@ -26,6 +26,12 @@ pub fn read(config: Config) -> Result<ExportContainer, ConvertError> {
 
 pub fn extract(path_to_xcstrings: &PathBuf) -> Result<ExportContainer, ConvertError> {
     let parsed = swift_localizable_json_parser::parse_from_file(path_to_xcstrings)?;
+
+    extract_from_parsed(&parsed)
+}
+
+/// The statistics per language of an already parsed catalog
+pub fn extract_from_parsed(parsed: &Parsed) -> Result<ExportContainer, ConvertError> {
     let loc_per_lang = parsed.localizable.localized_per_language();
     let mut export = vec![];
 
@@ -56,10 +62,9 @@ pub fn extract(path_to_xcstrings: &PathBuf) -> Result<ExportContainer, ConvertEr
 
         for single_loc in &loc.translations {
             if !all_keys_current_language.remove(&single_loc.key_raw) {
-                return Err(ConvertError::Wrapped(format!(
-                    "No key found for: {}",
-                    single_loc.key_raw
-                )));
+                return Err(ConvertError::MissingKey {
+                    key: single_loc.key_raw.clone(),
+                });
             }
 
             match &single_loc.translation {
@@ -85,10 +90,7 @@ pub fn extract(path_to_xcstrings: &PathBuf) -> Result<ExportContainer, ConvertEr
                     .translations
                     .iter()
                     .find(|t| t.key_raw == key)
-                    .ok_or(ConvertError::Wrapped(format!(
-                        "Key not found in base language: {}",
-                        key
-                    )))?
+                    .ok_or_else(|| ConvertError::MissingKey { key: key.clone() })?
                     .translation
                 {
                     Translation::Localization(_) => not_localized_keys += 1,
@@ -107,7 +109,7 @@ pub fn extract(path_to_xcstrings: &PathBuf) -> Result<ExportContainer, ConvertEr
 
     Ok(ExportContainer {
         export,
-        base_language: parsed.translation.source_language,
+        base_language: parsed.translation.source_language.clone(),
     })
 }
 
@@ -0,0 +1,62 @@
+use crate::error::ConvertError;
+use crate::xcstrings_docx_merger::convert::{merge_extracted, Export};
+use crate::xcstrings_po_merger::config::Config;
+use crate::xcstrings_po_merger::po_extractor::extract;
+
+pub fn merge(config: Config) -> Result<Export, ConvertError> {
+    let extracted = extract(&config.extract_from_po)?;
+
+    merge_extracted(
+        &config.base_xcstrings,
+        &config.updated_xcstrings,
+        &config.backups,
+        vec![extracted],
+    )
+}
+
+#[cfg(test)]
+mod test {
+    use crate::xcstrings_po_merger::config::Config;
+    use crate::xcstrings_po_merger::convert::merge;
+    use std::env::current_dir;
+
+    #[test]
+    fn test_merge() {
+        let base = current_dir().unwrap();
+        let save_in = base.join("generated").join("po_merge");
+        let xcstrings = base.join("resources").join("reader_test_base.xcstrings");
+        let xcstrings_updated = save_in.join("updated.xcstrings");
+
+        crate::po_writer::convert::convert_from_path(crate::po_writer::config::Config {
+            save_in: save_in.clone(),
+            clean_dir_before_generating: true,
+            new_language_codes: vec![],
+            path_to_xcstrings: xcstrings.clone(),
+        })
+        .unwrap();
+
+        // Translators fill in the plural in Poedit
+        let nl = save_in.join("nl.po");
+        let raw = std::fs::read_to_string(&nl)
+            .unwrap()
+            .replace("msgstr[1] \"\"", "msgstr[1] \"%1$lld meerdere\"");
+
+        std::fs::write(&nl, raw).unwrap();
+
+        let export = merge(Config {
+            extract_from_po: nl,
+            base_xcstrings: xcstrings,
+            updated_xcstrings: xcstrings_updated.clone(),
+            backups: Default::default(),
+        })
+        .unwrap();
+
+        assert_eq!(5, export.keys_translated);
+        assert_eq!(3, export.keys_to_translate);
+
+        let updated = std::fs::read_to_string(xcstrings_updated).unwrap();
+
+        assert!(updated.contains("\"value\" : \"Ingelijnd\\nmet \\n\\n newlines\""));
+        assert!(updated.contains("\"value\" : \"%1$lld meerdere\""));
+    }
+}
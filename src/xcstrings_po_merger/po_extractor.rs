@@ -0,0 +1,287 @@
+use std::path::PathBuf;
+
+use crate::error::ConvertError;
+use crate::plural_rules::gettext_plural_forms;
+use crate::po_writer::convert::{FUZZY_FLAG, PLURAL_FORMS_HEADER, VARIATIONS_COMMENT};
+use crate::rows::variate_from_key;
+use crate::xcstrings_docx_merger::docx_extractor::{Extract, ExtractContainer};
+use swift_localizable_json_parser::types::output::PluralVariate;
+
+const LANGUAGE_HEADER: &str = "Language:";
+
+/// A single entry of a po file, only the parts needed to merge
+#[derive(Clone, Debug, Default)]
+struct Entry {
+    variations: Vec<String>,
+    is_fuzzy: bool,
+    msgctxt: Option<String>,
+    msgid: Option<String>,
+    msgid_plural: Option<String>,
+    msgstr: Vec<String>,
+}
+
+pub fn extract(extract_from_po: &PathBuf) -> Result<ExtractContainer, ConvertError> {
+    if extract_from_po.exists() {
+        log::debug!("po file exists...");
+    } else {
+        return Err(ConvertError::MissingFile {
+            path: extract_from_po.clone(),
+        });
+    }
+
+    let raw =
+        std::fs::read_to_string(extract_from_po).map_err(ConvertError::io(extract_from_po))?;
+
+    extract_from_raw(&raw)
+}
+
+pub fn extract_from_raw(raw: &str) -> Result<ExtractContainer, ConvertError> {
+    let entries = entries(raw)?;
+    let header = entries
+        .iter()
+        .find(|e| e.msgctxt.is_none() && e.msgid.as_deref() == Some(""))
+        .and_then(|e| e.msgstr.first());
+    let language_code = match header_value(header, LANGUAGE_HEADER) {
+        Some(language_code) if !language_code.is_empty() => language_code,
+        _ => {
+            exit_with_log!("There is no Language in the po header, a template can not be merged");
+        }
+    };
+    let plural_forms = plural_forms_variates(header, &language_code)?;
+    let mut extracted = vec![];
+
+    for entry in entries {
+        let key = match entry.msgctxt {
+            None => {
+                if entry.msgid.as_deref() != Some("") {
+                    log::warn!("Skipping entry without msgctxt: {:?}", entry.msgid);
+                }
+
+                continue;
+            }
+            Some(key) => key,
+        };
+
+        // Fuzzy translations still need work, keep whatever is in the catalog
+        if entry.is_fuzzy {
+            log::debug!("Skipping fuzzy entry: {key}");
+
+            continue;
+        }
+
+        if entry.msgid_plural.is_none() {
+            if entry.msgstr.len() != 1 {
+                exit_with_log!(format!("Expected a single msgstr for key: {key}"));
+            }
+
+            extracted.push(Extract {
+                key,
+                variation: None,
+                device: None,
+                translated: entry.msgstr.into_iter().next().unwrap(),
+                state: None,
+            });
+
+            continue;
+        }
+
+        // The Plural-Forms decide the order of msgstr[n], files without them list the order in a comment
+        let variations = match plural_forms {
+            Some(variates) => variates.to_vec(),
+            None if entry.variations.is_empty() => {
+                exit_with_log!(format!(
+                    "There are no Plural-Forms in the po header for the plural key: {key}"
+                ));
+            }
+            None => {
+                let mut variations = vec![];
+
+                for variation in &entry.variations {
+                    match variate_from_key(variation) {
+                        None => {
+                            exit_with_log!(format!("Unknown variation {variation} for key: {key}"));
+                        }
+                        Some(variation) => variations.push(variation),
+                    }
+                }
+
+                variations
+            }
+        };
+
+        if variations.len() != entry.msgstr.len() {
+            exit_with_log!(format!(
+                "The amount of variations does not match the amount of msgstr[n] for key: {key}"
+            ));
+        }
+
+        for (variation, translated) in variations.into_iter().zip(entry.msgstr) {
+            extracted.push(Extract {
+                key: key.clone(),
+                variation: Some(variation),
+                device: None,
+                translated,
+                state: None,
+            });
+        }
+    }
+
+    Ok(ExtractContainer {
+        language_code,
+        extracted,
+        fingerprints: None,
+    })
+}
+
+fn header_value(header: Option<&String>, name: &str) -> Option<String> {
+    header?
+        .lines()
+        .find_map(|line| line.strip_prefix(name))
+        .map(|value| value.trim().to_string())
+}
+
+/// The variates per msgstr[n] of the Plural-Forms header, None without Plural-Forms.
+/// Editors may rewrite the expression, as long as the amount of plurals matches the bundled rule of the language it's accepted.
+fn plural_forms_variates(
+    header: Option<&String>,
+    language_code: &str,
+) -> Result<Option<&'static [PluralVariate]>, ConvertError> {
+    let plural_forms = match header_value(header, PLURAL_FORMS_HEADER) {
+        None => return Ok(None),
+        Some(plural_forms) => plural_forms,
+    };
+    let nplurals = plural_forms
+        .split(';')
+        .find_map(|part| part.trim().strip_prefix("nplurals="))
+        .and_then(|nplurals| nplurals.trim().parse::<usize>().ok());
+
+    match gettext_plural_forms(language_code) {
+        Some(bundled) if nplurals == Some(bundled.variates.len()) => Ok(Some(bundled.variates)),
+        Some(bundled) => {
+            exit_with_log!(format!(
+                "Unsupported Plural-Forms for {language_code}: {plural_forms}, expected: {}",
+                bundled.header()
+            ));
+        }
+        // Without a bundled rule only the Variations comment tells the order
+        None => Ok(None),
+    }
+}
+
+fn entries(raw: &str) -> Result<Vec<Entry>, ConvertError> {
+    let mut entries = vec![];
+    let mut entry = Entry::default();
+    // The value continuation strings are appended to
+    let mut current: Option<&mut String> = None;
+    // msgstr is the last part of an entry, anything but a continuation after it starts a new entry
+    let mut has_msgstr = false;
+
+    macro_rules! finish_entry {
+        () => {
+            if has_msgstr {
+                entries.push(std::mem::take(&mut entry));
+                has_msgstr = false;
+            }
+        };
+    }
+
+    for line in raw.trim_start_matches('\u{feff}').lines() {
+        let line = line.trim();
+
+        if line.is_empty() {
+            continue;
+        }
+
+        if line.starts_with('"') {
+            match current.as_mut() {
+                None => {
+                    exit_with_log!(format!("Unexpected string in po file: {line}"));
+                }
+                Some(current) => current.push_str(&unquote(line)?),
+            }
+
+            continue;
+        }
+
+        current = None;
+
+        // Obsolete entries
+        if line.starts_with("#~") {
+            continue;
+        }
+
+        if let Some(comment) = line.strip_prefix('#') {
+            finish_entry!();
+
+            if let Some(flags) = comment.strip_prefix(',') {
+                entry.is_fuzzy |= flags.split(',').any(|f| f.trim() == FUZZY_FLAG);
+            } else if let Some(variations) = comment.trim().strip_prefix(VARIATIONS_COMMENT) {
+                entry.variations = variations.split_whitespace().map(String::from).collect();
+            }
+
+            continue;
+        }
+
+        let (keyword, value) = match line.split_once(char::is_whitespace) {
+            None => {
+                exit_with_log!(format!("Unexpected line in po file: {line}"));
+            }
+            Some((keyword, value)) => (keyword, unquote(value.trim())?),
+        };
+
+        if keyword.starts_with("msgstr") {
+            has_msgstr = true;
+            entry.msgstr.push(value);
+            current = entry.msgstr.last_mut();
+
+            continue;
+        }
+
+        finish_entry!();
+
+        match keyword {
+            "msgctxt" => current = Some(entry.msgctxt.insert(value)),
+            "msgid" => current = Some(entry.msgid.insert(value)),
+            // The base value is already in the catalog, it only marks the entry as plural
+            "msgid_plural" => current = Some(entry.msgid_plural.insert(value)),
+            _ => {
+                exit_with_log!(format!("Unknown keyword in po file: {keyword}"));
+            }
+        }
+    }
+
+    if has_msgstr {
+        entries.push(entry);
+    }
+
+    Ok(entries)
+}
+
+fn unquote(quoted: &str) -> Result<String, ConvertError> {
+    let inner = match quoted.strip_prefix('"').and_then(|q| q.strip_suffix('"')) {
+        None => {
+            exit_with_log!(format!("Expected a quoted string in po file: {quoted}"));
+        }
+        Some(inner) => inner,
+    };
+    let mut unquoted = String::with_capacity(inner.len());
+    let mut chars = inner.chars();
+
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            unquoted.push(c);
+
+            continue;
+        }
+
+        match chars.next() {
+            Some('n') => unquoted.push('\n'),
+            Some('t') => unquoted.push('\t'),
+            Some('r') => unquoted.push('\r'),
+            Some(c) => unquoted.push(c),
+            None => unquoted.push('\\'),
+        }
+    }
+
+    Ok(unquoted)
+}
@@ -0,0 +1,19 @@
+use crate::backup::Backups;
+use clap::Parser;
+use std::path::PathBuf;
+
+#[derive(Clone, Debug, Parser)]
+pub struct Config {
+    /// The .xcstrings file to roll back
+    #[clap(long)]
+    pub xcstrings: PathBuf,
+    /// The file name of the backup to restore, the newest backup if not set
+    #[clap(long)]
+    pub backup: Option<String>,
+    /// Only lists the backups, nothing is restored
+    #[clap(long)]
+    pub list: bool,
+    /// The current catalog can be backed up as well before it is replaced
+    #[clap(flatten)]
+    pub backups: Backups,
+}
@@ -0,0 +1,146 @@
+use crate::backup::{list_backups, write_atomically};
+use crate::error::ConvertError;
+use crate::xcstrings_restore::config::Config;
+use serde::Serialize;
+
+#[derive(Clone, Debug, Serialize)]
+pub struct Export {
+    /// The file names of the backups, the newest first
+    pub backups: Vec<String>,
+    /// The file name of the restored backup
+    pub restored: Option<String>,
+}
+
+pub fn restore(config: Config) -> Result<Export, ConvertError> {
+    let backups = list_backups(&config.xcstrings)?;
+    let file_names = backups
+        .iter()
+        .map(|b| {
+            b.file_name()
+                .map(|f| f.to_string_lossy().to_string())
+                .unwrap_or_default()
+        })
+        .collect::<Vec<_>>();
+
+    if config.list {
+        return Ok(Export {
+            backups: file_names,
+            restored: None,
+        });
+    }
+
+    let index = match &config.backup {
+        None => 0,
+        Some(backup) => match file_names.iter().position(|f| f == backup) {
+            None => {
+                exit_with_log!(format!(
+                    "There is no backup named {backup}, available: {}",
+                    file_names.join(", ")
+                ));
+            }
+            Some(index) => index,
+        },
+    };
+    let backup = match backups.get(index) {
+        None => {
+            exit_with_log!(format!("There are no backups of {:#?}", config.xcstrings));
+        }
+        Some(backup) => backup,
+    };
+    // Read before writing, backing up the current catalog can remove the oldest backup
+    let contents = std::fs::read(backup).map_err(ConvertError::io(backup))?;
+
+    // Make sure a corrupted backup doesn't replace the catalog
+    crate::devices::parse(&contents)?;
+
+    log::debug!("Restoring {:#?} from {:#?}", config.xcstrings, backup);
+
+    write_atomically(&config.xcstrings, contents, &config.backups)?;
+
+    Ok(Export {
+        backups: file_names.clone(),
+        restored: Some(file_names[index].clone()),
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use crate::backup::Backups;
+    use crate::xcstrings_restore::config::Config;
+    use crate::xcstrings_restore::convert::restore;
+    use std::env::current_dir;
+
+    #[test]
+    fn test_restore() {
+        let base = current_dir().unwrap();
+        let resources = base.join("resources");
+        let save_in = base.join("generated").join("restore");
+        let xcstrings = save_in.join("Localizable.xcstrings");
+        let original =
+            std::fs::read_to_string(resources.join("reader_test_base.xcstrings")).unwrap();
+
+        let _ = std::fs::remove_dir_all(&save_in);
+        std::fs::create_dir_all(&save_in).unwrap();
+        std::fs::write(&xcstrings, &original).unwrap();
+
+        crate::xcstrings_docx_merger::convert::merge(
+            crate::xcstrings_docx_merger::config::Config {
+                extract_from_docx: resources.join("reader_test_updated_nl.docx"),
+                base_xcstrings: xcstrings.clone(),
+                updated_xcstrings: xcstrings.clone(),
+                backups: Backups { keep_backups: 3 },
+                dry_run: false,
+                diff_report: None,
+                conflict_policy: crate::xcstrings_docx_merger::config::ConflictPolicy::Skip,
+                placeholder_policy: crate::xcstrings_docx_merger::config::PlaceholderPolicy::Warn,
+                import_state: "translated".to_string(),
+                state_precedence: Default::default(),
+                state_mapping: vec![],
+            },
+        )
+        .unwrap();
+
+        assert_ne!(original, std::fs::read_to_string(&xcstrings).unwrap());
+
+        let config = |list| Config {
+            xcstrings: xcstrings.clone(),
+            backup: None,
+            list,
+            backups: Default::default(),
+        };
+
+        assert_eq!(1, restore(config(true)).unwrap().backups.len());
+
+        let export = restore(config(false)).unwrap();
+
+        assert!(export.restored.is_some());
+        assert_eq!(original, std::fs::read_to_string(&xcstrings).unwrap());
+    }
+
+    #[test]
+    fn test_restore_devices() {
+        let base = current_dir().unwrap();
+        let save_in = base.join("generated").join("restore_devices");
+        let xcstrings = save_in.join("Localizable.xcstrings");
+        let original =
+            std::fs::read_to_string(base.join("resources").join("device_test_base.xcstrings"))
+                .unwrap();
+
+        let _ = std::fs::remove_dir_all(&save_in);
+        std::fs::create_dir_all(&save_in).unwrap();
+        std::fs::write(&xcstrings, &original).unwrap();
+        crate::backup::write_atomically(&xcstrings, "{}", &Backups { keep_backups: 3 }).unwrap();
+
+        // Device variations are valid in a backup
+        let export = restore(Config {
+            xcstrings: xcstrings.clone(),
+            backup: None,
+            list: false,
+            backups: Default::default(),
+        })
+        .unwrap();
+
+        assert!(export.restored.is_some());
+        assert_eq!(original, std::fs::read_to_string(&xcstrings).unwrap());
+    }
+}
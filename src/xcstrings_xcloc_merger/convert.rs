@@ -0,0 +1,90 @@
+use crate::error::ConvertError;
+use crate::xcloc_writer::convert::{Contents, CONTENTS_FILE_NAME, LOCALIZED_CONTENTS_DIR};
+use crate::xcstrings_docx_merger::convert::{merge_extracted, Export};
+use crate::xcstrings_xcloc_merger::config::Config;
+use crate::xcstrings_xliff_merger::xliff_extractor::extract_from_raw_for_original;
+
+pub fn merge(config: Config) -> Result<Export, ConvertError> {
+    let contents_path = config.extract_from_xcloc.join(CONTENTS_FILE_NAME);
+
+    ConvertError::ensure_exists(&contents_path)?;
+
+    let contents: Contents = serde_json::from_str(
+        &std::fs::read_to_string(&contents_path).map_err(ConvertError::io(&contents_path))?,
+    )?;
+    let xliff = config
+        .extract_from_xcloc
+        .join(LOCALIZED_CONTENTS_DIR)
+        .join(format!("{}.xliff", contents.target_locale));
+
+    ConvertError::ensure_exists(&xliff)?;
+
+    let original = config
+        .base_xcstrings
+        .file_name()
+        .map(|f| f.to_string_lossy().to_string());
+    let extracted = extract_from_raw_for_original(
+        &std::fs::read_to_string(&xliff).map_err(ConvertError::io(&xliff))?,
+        original.as_deref(),
+    )?;
+
+    if extracted.language_code != contents.target_locale {
+        exit_with_log!(format!(
+            "Target locale of the xcloc ({}) does not match the xliff ({})",
+            contents.target_locale, extracted.language_code
+        ));
+    }
+
+    merge_extracted(
+        &config.base_xcstrings,
+        &config.updated_xcstrings,
+        &config.backups,
+        vec![extracted],
+    )
+}
+
+#[cfg(test)]
+mod test {
+    use crate::xcloc_writer::convert::CONTENTS_FILE_NAME;
+    use crate::xcstrings_xcloc_merger::config::Config;
+    use crate::xcstrings_xcloc_merger::convert::merge;
+    use std::env::current_dir;
+
+    #[test]
+    fn test_merge() {
+        let base = current_dir().unwrap();
+        let save_in = base.join("generated").join("xcloc_merge");
+        let xcstrings = base.join("resources").join("reader_test_base.xcstrings");
+
+        crate::xcloc_writer::convert::convert_from_path(crate::xcloc_writer::config::Config {
+            save_in: save_in.clone(),
+            clean_dir_before_generating: true,
+            new_language_codes: vec!["pl".to_string()],
+            path_to_xcstrings: xcstrings.clone(),
+        })
+        .unwrap();
+
+        let export = merge(Config {
+            extract_from_xcloc: save_in.join("nl.xcloc"),
+            base_xcstrings: xcstrings,
+            updated_xcstrings: save_in.join("updated.xcstrings"),
+            backups: Default::default(),
+        })
+        .unwrap();
+
+        assert_eq!(4, export.keys_translated);
+        assert_eq!(4, export.keys_to_translate);
+
+        std::fs::remove_file(save_in.join("pl.xcloc").join(CONTENTS_FILE_NAME)).unwrap();
+
+        let missing = merge(Config {
+            extract_from_xcloc: save_in.join("pl.xcloc"),
+            base_xcstrings: base.join("resources").join("reader_test_base.xcstrings"),
+            updated_xcstrings: save_in.join("updated.xcstrings"),
+            backups: Default::default(),
+        })
+        .unwrap_err();
+
+        assert_eq!("missing_file", missing.code());
+    }
+}
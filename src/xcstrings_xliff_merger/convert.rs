@@ -0,0 +1,92 @@
+use crate::error::ConvertError;
+use crate::xcstrings_docx_merger::convert::{merge_extracted, Export};
+use crate::xcstrings_xliff_merger::config::Config;
+use crate::xcstrings_xliff_merger::xliff_extractor::extract;
+
+pub fn merge(config: Config) -> Result<Export, ConvertError> {
+    let extracted = extract(&config.extract_from_xliff)?;
+
+    merge_extracted(
+        &config.base_xcstrings,
+        &config.updated_xcstrings,
+        &config.backups,
+        vec![extracted],
+    )
+}
+
+#[cfg(test)]
+mod test {
+    use crate::xcstrings_xliff_merger::config::Config;
+    use crate::xcstrings_xliff_merger::convert::merge;
+    use std::env::current_dir;
+    use swift_localizable_json_parser::types::input::TranslationTypeContainer;
+    use swift_localizable_json_parser::types::output::Translation;
+
+    #[test]
+    fn test_merge() {
+        let base = current_dir().unwrap();
+        let save_in = base.join("generated").join("xliff_merge");
+        let xcstrings = base.join("resources").join("reader_test_base.xcstrings");
+        let xcstrings_updated = save_in.join("updated.xcstrings");
+
+        crate::xliff_writer::convert::convert_from_path(crate::xliff_writer::config::Config {
+            save_in: save_in.clone(),
+            clean_dir_before_generating: true,
+            new_language_codes: vec![],
+            path_to_xcstrings: xcstrings.clone(),
+        })
+        .unwrap();
+
+        // Fill in the missing translation like a translator would do, without marking it as done
+        let nl = save_in.join("nl.xliff");
+        let raw = std::fs::read_to_string(&nl).unwrap().replace(
+            "<source>normal</source>\n        <target state=\"new\"></target>",
+            "<source>normal</source>\n        <target state=\"new\">normaal &amp; meer</target>",
+        );
+
+        std::fs::write(&nl, raw).unwrap();
+
+        let export = merge(Config {
+            extract_from_xliff: nl,
+            base_xcstrings: xcstrings,
+            updated_xcstrings: xcstrings_updated.clone(),
+            backups: Default::default(),
+        })
+        .unwrap();
+
+        assert_eq!(5, export.keys_translated);
+
+        let parsed = swift_localizable_json_parser::parse_from_file(&xcstrings_updated).unwrap();
+        let nl = parsed
+            .localizable
+            .localized_per_language()
+            .language_localized
+            .remove("nl")
+            .unwrap();
+        let value_of = |key: &str| match &nl
+            .translations
+            .iter()
+            .find(|t| t.key_raw == key)
+            .unwrap()
+            .translation
+        {
+            Translation::Localization(l) => l.value.clone(),
+            Translation::PluralVariation(pv) => pv[0].translation_value.value.clone(),
+        };
+
+        assert_eq!("normaal & meer", value_of("normal_1"));
+        assert_eq!(
+            "Ingelijnd\nmet \n\n newlines",
+            value_of("Inlined\nwith \n\n newlines")
+        );
+        assert_eq!("%1$lld eentje", value_of("plurals"));
+
+        let state_of = |key: &str| match &parsed.translation.strings[key].localizations["nl"] {
+            TranslationTypeContainer::StringUnit(su) => su.string_unit.state.clone(),
+            TranslationTypeContainer::Variation(_) => unreachable!(),
+        };
+
+        assert_eq!("needs_review", state_of("normal_1"));
+        assert_eq!("translated", state_of("normal_0"));
+    }
+}
@@ -0,0 +1,126 @@
+use std::ffi::OsStr;
+use std::path::{Path, PathBuf};
+
+use crate::error::ConvertError;
+use crate::xcstrings_docx_merger::docx_extractor::{Extract, ExtractContainer};
+use crate::xliff_writer::convert::split_unit_id;
+use crate::xml::text_of;
+use crate::NEEDS_REVIEW_STATE;
+use swift_localizable_json_parser::TRANSLATED_STATE;
+
+pub fn extract(extract_from_xliff: &PathBuf) -> Result<ExtractContainer, ConvertError> {
+    if extract_from_xliff.exists() {
+        log::debug!("xliff file exists...");
+    } else {
+        return Err(ConvertError::MissingFile {
+            path: extract_from_xliff.clone(),
+        });
+    }
+
+    let raw = std::fs::read_to_string(extract_from_xliff)
+        .map_err(ConvertError::io(extract_from_xliff))?;
+
+    extract_from_raw(&raw)
+}
+
+pub fn extract_from_raw(raw: &str) -> Result<ExtractContainer, ConvertError> {
+    extract_from_raw_for_original(raw, None)
+}
+
+/// Xcode writes a file element per localizable file, when an original file name is given,
+/// only the file elements of that file are extracted (if there are any)
+pub fn extract_from_raw_for_original(
+    raw: &str,
+    original: Option<&str>,
+) -> Result<ExtractContainer, ConvertError> {
+    let document = roxmltree::Document::parse(raw)?;
+    let mut language_code: Option<String> = None;
+    let mut extracted = vec![];
+    let mut files = document
+        .descendants()
+        .filter(|n| n.has_tag_name("file"))
+        .collect::<Vec<_>>();
+
+    if let Some(original) = original {
+        let is_original = |file: &roxmltree::Node| {
+            file.attribute("original")
+                .map(|o| Path::new(o).file_name() == Some(OsStr::new(original)))
+                .unwrap_or(false)
+        };
+
+        if files.iter().any(is_original) {
+            files.retain(is_original);
+        }
+    }
+
+    for file in files {
+        let target_language = match file.attribute("target-language") {
+            None => {
+                exit_with_log!("There is no target-language in the file element");
+            }
+            Some(target_language) => target_language,
+        };
+
+        match &language_code {
+            Some(language_code) if language_code != target_language => {
+                exit_with_log!(format!(
+                    "Expected a single target language, found: {language_code} and {target_language}"
+                ));
+            }
+            _ => language_code = Some(target_language.to_string()),
+        }
+
+        for trans_unit in file.descendants().filter(|n| n.has_tag_name("trans-unit")) {
+            let id = match trans_unit.attribute("id") {
+                None => {
+                    exit_with_log!("Found trans-unit without an id");
+                }
+                Some(id) => id,
+            };
+            let (key, variation) = split_unit_id(id)?;
+            let target = trans_unit.children().find(|n| n.has_tag_name("target"));
+            let translated = target.map(text_of).unwrap_or_default();
+            let state = target
+                .and_then(|t| t.attribute("state"))
+                .and_then(catalog_state)
+                .map(str::to_string);
+
+            extracted.push(Extract {
+                key,
+                variation,
+                device: None,
+                translated,
+                state,
+            });
+        }
+    }
+
+    let language_code = match language_code {
+        None => {
+            exit_with_log!("There is no file element in the xliff");
+        }
+        Some(language_code) => language_code,
+    };
+
+    Ok(ExtractContainer {
+        language_code,
+        extracted,
+        fingerprints: None,
+    })
+}
+
+/// Maps the XLIFF 1.2 state attribute of a target to the catalog state, the reverse of [crate::xliff_writer::convert::xliff_state].
+/// Anything a CAT tool didn't mark as done needs a review, unknown states (e.g.: x-custom) are left to the [crate::xcstrings_docx_merger::states::StateMapping].
+fn catalog_state(state: &str) -> Option<&'static str> {
+    match state {
+        "translated" | "signed-off" | "final" => Some(TRANSLATED_STATE),
+        "new"
+        | "needs-translation"
+        | "needs-l10n"
+        | "needs-adaptation"
+        | "needs-review-translation"
+        | "needs-review-l10n"
+        | "needs-review-adaptation" => Some(NEEDS_REVIEW_STATE),
+        _ => None,
+    }
+}
@@ -0,0 +1,50 @@
+use crate::error::ConvertError;
+use crate::xcstrings_docx_merger::convert::{merge_extracted, Export};
+use crate::xcstrings_xlsx_merger::config::Config;
+use crate::xcstrings_xlsx_merger::xlsx_extractor::extract;
+
+pub fn merge(config: Config) -> Result<Export, ConvertError> {
+    let extracted = extract(&config.extract_from_xlsx)?;
+
+    merge_extracted(
+        &config.base_xcstrings,
+        &config.updated_xcstrings,
+        &config.backups,
+        extracted,
+    )
+}
+
+#[cfg(test)]
+mod test {
+    use crate::xcstrings_xlsx_merger::config::Config;
+    use crate::xcstrings_xlsx_merger::convert::merge;
+    use std::env::current_dir;
+
+    #[test]
+    fn test_merge() {
+        let base = current_dir().unwrap();
+        let save_in = base.join("generated").join("xlsx_merge");
+        let xcstrings = base.join("resources").join("reader_test_base.xcstrings");
+
+        crate::xlsx_writer::convert::convert_from_path(crate::xlsx_writer::config::Config {
+            save_in: save_in.clone(),
+            clean_dir_before_generating: true,
+            new_language_codes: vec![],
+            path_to_xcstrings: xcstrings.clone(),
+            columns_in_output: vec![],
+            lock_non_translation_columns: false,
+        })
+        .unwrap();
+
+        let export = merge(Config {
+            extract_from_xlsx: save_in.join("nl.xlsx"),
+            base_xcstrings: xcstrings,
+            updated_xcstrings: save_in.join("updated.xcstrings"),
+            backups: Default::default(),
+        })
+        .unwrap();
+
+        assert_eq!(4, export.keys_translated);
+        assert_eq!(4, export.keys_to_translate);
+    }
+}
@@ -0,0 +1,29 @@
+use calamine::{open_workbook, Reader, Xlsx};
+use std::path::PathBuf;
+
+use crate::error::ConvertError;
+use crate::xcstrings_docx_merger::docx_extractor::{extract_from_rows, ExtractContainer};
+
+pub fn extract(extract_from_xlsx: &PathBuf) -> Result<Vec<ExtractContainer>, ConvertError> {
+    if extract_from_xlsx.exists() {
+        log::debug!("xlsx file exists...");
+    } else {
+        return Err(ConvertError::MissingFile {
+            path: extract_from_xlsx.clone(),
+        });
+    }
+
+    let mut workbook: Xlsx<_> = open_workbook(extract_from_xlsx)?;
+    let range = match workbook.worksheet_range_at(0) {
+        None => {
+            exit_with_log!("There is no worksheet in the xlsx file");
+        }
+        Some(range) => range?,
+    };
+    let rows = range
+        .rows()
+        .map(|row| row.iter().map(|cell| cell.to_string()).collect::<Vec<_>>())
+        .collect::<Vec<_>>();
+
+    extract_from_rows(rows, &[])
+}
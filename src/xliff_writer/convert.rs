@@ -0,0 +1,207 @@
+use crate::docx_writer::convert::Export;
+use crate::error::ConvertError;
+use crate::rows::{languages_to_write, rows_for_language, variate_from_key, Row};
+use crate::xliff_writer::config::Config;
+use crate::xml::escape;
+use crate::{NEEDS_REVIEW_STATE, STALE_STATE};
+use swift_localizable_json_parser::types::output::{Parsed, PluralVariate};
+use swift_localizable_json_parser::TRANSLATED_STATE;
+
+/// Used as the original file name if the catalog isn't read from a file
+const DEFAULT_ORIGINAL: &str = "Localizable.xcstrings";
+/// Same separator Xcode uses for variations in the trans-unit id
+const PLURAL_UNIT_ID_SEPARATOR: &str = "|==|plural.";
+
+pub fn convert_from_path(config: Config) -> Result<Vec<Export>, ConvertError> {
+    ConvertError::ensure_exists(&config.path_to_xcstrings)?;
+
+    let read = std::fs::read(&config.path_to_xcstrings)
+        .map_err(ConvertError::io(&config.path_to_xcstrings))?;
+
+    convert_from_raw(&read, config)
+}
+
+pub fn convert_from_raw(xcstrings: &[u8], config: Config) -> Result<Vec<Export>, ConvertError> {
+    convert(
+        swift_localizable_json_parser::parse_from_bytes(xcstrings)?,
+        config,
+    )
+}
+
+pub fn convert(localizable: Parsed, config: Config) -> Result<Vec<Export>, ConvertError> {
+    let localized_per_language = localizable.localizable.localized_per_language();
+
+    if config.clean_dir_before_generating {
+        // Dir does not have to exist
+        let _ = std::fs::remove_dir_all(&config.save_in);
+    }
+
+    // Ignore any error
+    let _ = std::fs::create_dir_all(&config.save_in);
+
+    let base_language = &localizable.translation.source_language;
+    let original = config
+        .path_to_xcstrings
+        .file_name()
+        .map(|f| f.to_string_lossy().to_string())
+        .unwrap_or(DEFAULT_ORIGINAL.to_string());
+    let mut exports = vec![];
+
+    for language_to_write in languages_to_write(
+        &localized_per_language,
+        base_language,
+        config.new_language_codes,
+    ) {
+        log::debug!("Writing xliff for language: {language_to_write}");
+
+        let rows = rows_for_language(&localized_per_language, base_language, &language_to_write);
+        let xliff = to_xliff(base_language, &language_to_write, &original, &rows);
+        let file_name = format!("{}.xliff", language_to_write);
+
+        let path = config.save_in.join(&file_name);
+
+        std::fs::write(&path, xliff).map_err(ConvertError::io(&path))?;
+
+        exports.push(Export {
+            amount_keys_to_translate: rows.len() as i32,
+            amount_keys_total: rows.len() as i32,
+            language_code: language_to_write,
+            file_name,
+        })
+    }
+
+    Ok(exports)
+}
+
+/// Creates an XLIFF 1.2 document with a single file element for the target language
+pub fn to_xliff(base_language: &str, language: &str, original: &str, rows: &[Row]) -> String {
+    let mut xml = vec![
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>".to_string(),
+        "<xliff xmlns=\"urn:oasis:names:tc:xliff:document:1.2\" version=\"1.2\">".to_string(),
+        format!(
+            "  <file original=\"{}\" source-language=\"{}\" target-language=\"{}\" datatype=\"plaintext\">",
+            escape(original),
+            escape(base_language),
+            escape(language)
+        ),
+        "    <body>".to_string(),
+    ];
+    let mut open_group: Option<&str> = None;
+
+    for row in rows {
+        if open_group.is_some() && open_group != Some(row.key.as_str()) {
+            xml.push("      </group>".to_string());
+            open_group = None;
+        }
+
+        let indent = if row.variation.is_some() {
+            if open_group.is_none() {
+                xml.push(format!(
+                    "      <group id=\"{}\" restype=\"x-gettext-plurals\">",
+                    escape(&row.key)
+                ));
+
+                if !row.comment.is_empty() {
+                    xml.push(format!("        <note>{}</note>", escape(&row.comment)));
+                }
+
+                open_group = Some(&row.key);
+            }
+
+            "        "
+        } else {
+            "      "
+        };
+
+        xml.push(format!(
+            "{indent}<trans-unit id=\"{}\" xml:space=\"preserve\">",
+            escape(&row.unit_id())
+        ));
+        xml.push(format!(
+            "{indent}  <source>{}</source>",
+            escape(&row.base_value)
+        ));
+        xml.push(format!(
+            "{indent}  <target state=\"{}\">{}</target>",
+            xliff_state(&row.state),
+            escape(&row.translated_value)
+        ));
+
+        if row.variation.is_none() && !row.comment.is_empty() {
+            xml.push(format!("{indent}  <note>{}</note>", escape(&row.comment)));
+        }
+
+        xml.push(format!("{indent}</trans-unit>"));
+    }
+
+    if open_group.is_some() {
+        xml.push("      </group>".to_string());
+    }
+
+    xml.push("    </body>".to_string());
+    xml.push("  </file>".to_string());
+    xml.push("</xliff>".to_string());
+
+    xml.join("\n")
+}
+
+/// The key for a single string, for plurals the same format Xcode uses, e.g.: key|==|plural.one
+pub fn unit_id(key: &str, variation: Option<&PluralVariate>) -> String {
+    match variation {
+        None => key.to_string(),
+        Some(variation) => format!(
+            "{key}{PLURAL_UNIT_ID_SEPARATOR}{}",
+            variation.android_key().to_lowercase()
+        ),
+    }
+}
+
+/// The reverse of [unit_id]
+pub fn split_unit_id(id: &str) -> Result<(String, Option<PluralVariate>), ConvertError> {
+    match id.rsplit_once(PLURAL_UNIT_ID_SEPARATOR) {
+        None => Ok((id.to_string(), None)),
+        Some((key, variate)) => match variate_from_key(variate) {
+            None => Err(ConvertError::from(format!(
+                "Unknown plural variation {variate} for key: {key}"
+            ))),
+            Some(variation) => Ok((key.to_string(), Some(variation))),
+        },
+    }
+}
+
+/// Maps the .xcstrings state to the XLIFF 1.2 state attribute
+pub fn xliff_state(state: &str) -> &'static str {
+    match state {
+        TRANSLATED_STATE => "translated",
+        NEEDS_REVIEW_STATE | STALE_STATE => "needs-review-translation",
+        _ => "new",
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::xliff_writer::config::Config;
+    use std::env::current_dir;
+
+    #[test]
+    fn test_convert() {
+        let base = current_dir().unwrap();
+        let save_in = base.join("generated").join("xliff");
+        let exports = super::convert_from_path(Config {
+            save_in: save_in.clone(),
+            clean_dir_before_generating: true,
+            new_language_codes: vec!["pl".to_string()],
+            path_to_xcstrings: base.join("resources").join("reader_test_base.xcstrings"),
+        })
+        .unwrap();
+
+        assert_eq!(2, exports.len());
+
+        let nl = std::fs::read_to_string(save_in.join("nl.xliff")).unwrap();
+
+        assert!(nl.contains("target-language=\"nl\""));
+        assert!(nl.contains("<trans-unit id=\"plurals|==|plural.one\" xml:space=\"preserve\">"));
+        assert!(nl.contains("<target state=\"translated\">%1$lld eentje</target>"));
+        assert!(nl.contains("<note>some random comment</note>"));
+    }
+}
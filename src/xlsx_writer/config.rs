@@ -0,0 +1,23 @@
+use crate::docx_writer::config::Column;
+use clap::Parser;
+
+use std::path::PathBuf;
+
+#[derive(Clone, Debug, Parser)]
+pub struct Config {
+    #[clap(long)]
+    pub save_in: PathBuf,
+    #[clap(long)]
+    pub clean_dir_before_generating: bool,
+    /// If you added a new language, add it to the list so a new xlsx file can be generated
+    #[clap(long)]
+    pub new_language_codes: Vec<String>,
+    #[clap(long)]
+    pub path_to_xcstrings: PathBuf,
+    #[clap(long)]
+    pub columns_in_output: Vec<Column>,
+    /// Protects the sheet so only the translation column can be edited.
+    /// Filtering keeps working, Excel doesn't allow sorting locked cells though.
+    #[clap(long)]
+    pub lock_non_translation_columns: bool,
+}
@@ -0,0 +1,156 @@
+use crate::docx_writer::convert::Export;
+use crate::error::ConvertError;
+use crate::rows::{header, languages_to_write, rows_for_language};
+use crate::xlsx_writer::config::Config;
+use rust_xlsxwriter::{Format, ProtectionOptions, Workbook};
+use swift_localizable_json_parser::types::output::Parsed;
+
+const WIDTH_KEY_COLUMN: f64 = 40.;
+const WIDTH_TEXT_COLUMN: f64 = 60.;
+
+pub fn convert_from_path(config: Config) -> Result<Vec<Export>, ConvertError> {
+    ConvertError::ensure_exists(&config.path_to_xcstrings)?;
+
+    let read = std::fs::read(&config.path_to_xcstrings)
+        .map_err(ConvertError::io(&config.path_to_xcstrings))?;
+
+    convert_from_raw(&read, config)
+}
+
+pub fn convert_from_raw(xcstrings: &[u8], config: Config) -> Result<Vec<Export>, ConvertError> {
+    convert(
+        swift_localizable_json_parser::parse_from_bytes(xcstrings)?,
+        config,
+    )
+}
+
+pub fn convert(localizable: Parsed, config: Config) -> Result<Vec<Export>, ConvertError> {
+    let localized_per_language = localizable.localizable.localized_per_language();
+
+    if config.clean_dir_before_generating {
+        // Dir does not have to exist
+        let _ = std::fs::remove_dir_all(&config.save_in);
+    }
+
+    // Ignore any error
+    let _ = std::fs::create_dir_all(&config.save_in);
+
+    let base_language = &localizable.translation.source_language;
+    let header_format = Format::new().set_bold();
+    let locked_format = Format::new().set_text_wrap();
+    let unlocked_format = Format::new().set_text_wrap().set_unlocked();
+    let mut exports = vec![];
+
+    for language_to_write in languages_to_write(
+        &localized_per_language,
+        base_language,
+        config.new_language_codes.clone(),
+    ) {
+        log::debug!("Writing xlsx for language: {language_to_write}");
+
+        let mut workbook = Workbook::new();
+        let worksheet = workbook.add_worksheet();
+        let header = header(base_language, &language_to_write, &config.columns_in_output);
+        let index_translated = (header.len() - 1) as u16;
+
+        for (column, text) in header.iter().enumerate() {
+            let column = column as u16;
+            let width = if column == 0 {
+                WIDTH_KEY_COLUMN
+            } else if column == 1 || column + 1 >= index_translated {
+                WIDTH_TEXT_COLUMN
+            } else {
+                // Keep the default width for the small columns
+                0.
+            };
+
+            worksheet.write_string_with_format(0, column, text, &header_format)?;
+
+            if width > 0. {
+                worksheet.set_column_width(column, width)?;
+            }
+        }
+
+        let rows = rows_for_language(&localized_per_language, base_language, &language_to_write);
+
+        for (index, row) in rows.iter().enumerate() {
+            let row_index = (index + 1) as u32;
+
+            for (column, value) in row.cells(&config.columns_in_output).iter().enumerate() {
+                let column = column as u16;
+                let format = if column == index_translated {
+                    &unlocked_format
+                } else {
+                    &locked_format
+                };
+
+                worksheet.write_string_with_format(row_index, column, value, format)?;
+            }
+        }
+
+        worksheet.set_freeze_panes(1, 0)?;
+        worksheet.autofilter(0, 0, rows.len() as u32, index_translated)?;
+
+        if config.lock_non_translation_columns {
+            worksheet.protect_with_options(&ProtectionOptions {
+                select_locked_cells: true,
+                select_unlocked_cells: true,
+                format_columns: true,
+                format_rows: true,
+                sort: true,
+                use_autofilter: true,
+                ..Default::default()
+            });
+        }
+
+        let file_name = format!("{}.xlsx", language_to_write);
+
+        workbook.save(config.save_in.join(&file_name))?;
+
+        exports.push(Export {
+            amount_keys_to_translate: rows.len() as i32,
+            amount_keys_total: rows.len() as i32,
+            language_code: language_to_write,
+            file_name,
+        })
+    }
+
+    Ok(exports)
+}
+
+#[cfg(test)]
+mod test {
+    use crate::docx_writer::config::Column;
+    use crate::xlsx_writer::config::Config;
+    use std::env::current_dir;
+
+    #[test]
+    fn test_convert() {
+        let base = current_dir().unwrap();
+        let save_in = base.join("generated").join("xlsx");
+        let exports = super::convert_from_path(Config {
+            save_in: save_in.clone(),
+            clean_dir_before_generating: true,
+            new_language_codes: vec!["pl".to_string()],
+            path_to_xcstrings: base.join("resources").join("reader_test_base.xcstrings"),
+            columns_in_output: vec![Column::State],
+            lock_non_translation_columns: true,
+        })
+        .unwrap();
+
+        assert_eq!(2, exports.len());
+
+        let extracted =
+            crate::xcstrings_xlsx_merger::xlsx_extractor::extract(&save_in.join("nl.xlsx"))
+                .unwrap()
+                .remove(0);
+        let newlines = extracted
+            .extracted
+            .iter()
+            .find(|e| e.key == "Inlined\nwith \n\n newlines")
+            .unwrap();
+
+        assert_eq!("nl", extracted.language_code);
+        assert_eq!("Ingelijnd\nmet \n\n newlines", newlines.translated);
+    }
+}
@@ -0,0 +1,29 @@
+/// Escapes text so it can be placed inside an element or an attribute.
+/// Newlines and tabs are written as character references, else attribute normalization turns them into spaces.
+pub fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+
+    for c in text.chars() {
+        match c {
+            '&' => escaped += "&amp;",
+            '<' => escaped += "&lt;",
+            '>' => escaped += "&gt;",
+            '"' => escaped += "&quot;",
+            '\'' => escaped += "&apos;",
+            '\n' => escaped += "&#10;",
+            '\r' => escaped += "&#13;",
+            '\t' => escaped += "&#9;",
+            c => escaped.push(c),
+        }
+    }
+
+    escaped
+}
+
+/// All the text inside a node, including the text of inline child elements
+pub fn text_of(node: roxmltree::Node) -> String {
+    node.descendants()
+        .filter(|n| n.is_text())
+        .filter_map(|n| n.text())
+        .collect()
+}